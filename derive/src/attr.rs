@@ -0,0 +1,312 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::{format, vec::Vec};
+use core::convert::TryFrom;
+use syn::{
+	parse::Result, punctuated::Punctuated, token::Comma, Attribute, Error, Lit, LitInt, LitStr, Meta, NestedMeta, Path,
+	Type, WherePredicate,
+};
+
+/// The `#[type_metadata(...)]` helper attributes of a type.
+#[derive(Default)]
+pub struct ContainerAttrs {
+	/// Describes the serde data model of the type by interpreting its `#[serde(...)]` attributes.
+	pub serde: bool,
+	/// Replaces the inferred where clause predicates of the derived impls.
+	pub bound: Option<Vec<WherePredicate>>,
+	/// The path of the `type_metadata` crate, e.g. if it is re-exported by another crate.
+	pub krate: Option<Path>,
+}
+
+impl ContainerAttrs {
+	/// Parses the helper attributes of a type.
+	///
+	/// Returns a spanned error for unknown, duplicate or malformed keys.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut container_attrs = Self::default();
+		for meta in helper_metas(attrs)? {
+			match &meta {
+				Meta::Word(ident) if ident == "serde" => {
+					if container_attrs.serde {
+						return Err(duplicate(&meta));
+					}
+					container_attrs.serde = true;
+				}
+				Meta::NameValue(name_value) if name_value.ident == "bound" => {
+					if container_attrs.bound.is_some() {
+						return Err(duplicate(&meta));
+					}
+					container_attrs.bound = Some(predicates(&name_value.lit)?);
+				}
+				Meta::NameValue(name_value) if name_value.ident == "crate" => {
+					if container_attrs.krate.is_some() {
+						return Err(duplicate(&meta));
+					}
+					container_attrs.krate = Some(lit_str(&name_value.lit)?.parse()?);
+				}
+				_ => return Err(unknown(&meta)),
+			}
+		}
+		Ok(container_attrs)
+	}
+}
+
+/// The `#[type_metadata(...)]` helper attributes of a field.
+#[derive(Default)]
+pub struct FieldAttrs {
+	/// Omits the field from the type definition.
+	pub skip: bool,
+	/// Overrides the name of the field.
+	pub rename: Option<LitStr>,
+	/// Describes the field as this type instead of its declared type.
+	pub with: Option<Type>,
+	/// Replaces the where clause predicates inferred from the field type.
+	pub bound: Option<Vec<WherePredicate>>,
+}
+
+impl FieldAttrs {
+	/// Parses the helper attributes of a field.
+	///
+	/// Returns a spanned error for unknown, duplicate or malformed keys.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut field_attrs = Self::default();
+		for meta in helper_metas(attrs)? {
+			match &meta {
+				Meta::Word(ident) if ident == "skip" => {
+					if field_attrs.skip {
+						return Err(duplicate(&meta));
+					}
+					field_attrs.skip = true;
+				}
+				Meta::NameValue(name_value) if name_value.ident == "rename" => {
+					if field_attrs.rename.is_some() {
+						return Err(duplicate(&meta));
+					}
+					field_attrs.rename = Some(lit_str(&name_value.lit)?);
+				}
+				Meta::NameValue(name_value) if name_value.ident == "with" => {
+					if field_attrs.with.is_some() {
+						return Err(duplicate(&meta));
+					}
+					field_attrs.with = Some(lit_str(&name_value.lit)?.parse()?);
+				}
+				Meta::NameValue(name_value) if name_value.ident == "bound" => {
+					if field_attrs.bound.is_some() {
+						return Err(duplicate(&meta));
+					}
+					field_attrs.bound = Some(predicates(&name_value.lit)?);
+				}
+				_ => return Err(unknown(&meta)),
+			}
+		}
+		Ok(field_attrs)
+	}
+}
+
+/// The `#[type_metadata(...)]` helper attributes of an enum variant.
+#[derive(Default)]
+pub struct VariantAttrs {
+	/// Overrides the index by which the variant is encoded.
+	pub index: Option<LitInt>,
+}
+
+impl VariantAttrs {
+	/// Parses the helper attributes of an enum variant.
+	///
+	/// Returns a spanned error for unknown, duplicate or malformed keys.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut variant_attrs = Self::default();
+		for meta in helper_metas(attrs)? {
+			match &meta {
+				Meta::NameValue(name_value) if name_value.ident == "index" => {
+					if variant_attrs.index.is_some() {
+						return Err(duplicate(&meta));
+					}
+					variant_attrs.index = match &name_value.lit {
+						Lit::Int(lit_int) if u8::try_from(lit_int.value()).is_ok() => Some(lit_int.clone()),
+						lit => return Err(Error::new_spanned(lit, "expected `u8` integer literal")),
+					};
+				}
+				_ => return Err(unknown(&meta)),
+			}
+		}
+		Ok(variant_attrs)
+	}
+}
+
+/// Returns the items of all `#[type_metadata(...)]` attributes in order.
+fn helper_metas(attrs: &[Attribute]) -> Result<Vec<Meta>> {
+	let mut metas = Vec::new();
+	for attr in attrs {
+		if !attr.path.is_ident("type_metadata") {
+			continue;
+		}
+		match attr.parse_meta()? {
+			Meta::List(list) => {
+				for nested in list.nested {
+					match nested {
+						NestedMeta::Meta(meta) => metas.push(meta),
+						NestedMeta::Literal(lit) => {
+							return Err(Error::new_spanned(lit, "expected `key` or `key = \"value\"`"))
+						}
+					}
+				}
+			}
+			meta => return Err(Error::new_spanned(meta, "expected `#[type_metadata(...)]`")),
+		}
+	}
+	Ok(metas)
+}
+
+pub fn lit_str(lit: &Lit) -> Result<LitStr> {
+	match lit {
+		Lit::Str(lit_str) => Ok(lit_str.clone()),
+		_ => Err(Error::new_spanned(lit, "expected string literal")),
+	}
+}
+
+/// Parses comma separated where clause predicates such as `"T: Trait, U::Item: Trait"`.
+fn predicates(lit: &Lit) -> Result<Vec<WherePredicate>> {
+	let predicates = lit_str(lit)?.parse_with(Punctuated::<WherePredicate, Comma>::parse_terminated)?;
+	Ok(predicates.into_iter().collect())
+}
+
+fn unknown(meta: &Meta) -> Error {
+	Error::new_spanned(meta, format!("unknown type_metadata attribute `{}`", meta.name()))
+}
+
+fn duplicate(meta: &Meta) -> Error {
+	Error::new_spanned(meta, format!("duplicate type_metadata attribute `{}`", meta.name()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use syn::{parse_quote, Data, DeriveInput};
+
+	fn parse(input: DeriveInput) -> Result<FieldAttrs> {
+		match input.data {
+			Data::Struct(data) => FieldAttrs::parse(&data.fields.iter().next().unwrap().attrs),
+			_ => unreachable!("only structs are parsed"),
+		}
+	}
+
+	#[test]
+	fn field_attrs() {
+		let attrs = parse(parse_quote! {
+			struct S {
+				#[doc = " Ignored."]
+				#[type_metadata(rename = "wire", with = "Vec<u8>")]
+				#[type_metadata(skip, bound = "T: Clone")]
+				field: u32,
+			}
+		})
+		.unwrap();
+		assert!(attrs.skip);
+		assert_eq!(attrs.rename.unwrap().value(), "wire");
+		let expected: Type = parse_quote!(Vec<u8>);
+		assert_eq!(attrs.with, Some(expected));
+		let expected: WherePredicate = parse_quote!(T: Clone);
+		assert_eq!(attrs.bound, Some(vec![expected]));
+	}
+
+	#[test]
+	fn container_attrs() {
+		let input: DeriveInput = parse_quote! {
+			#[type_metadata(serde)]
+			struct S;
+		};
+		assert!(ContainerAttrs::parse(&input.attrs).unwrap().serde);
+		let input: DeriveInput = parse_quote! {
+			#[type_metadata(bound = "T: Metadata + 'static, T::Item: Metadata,")]
+			struct S;
+		};
+		let expected: Vec<WherePredicate> = vec![parse_quote!(T: Metadata + 'static), parse_quote!(T::Item: Metadata)];
+		assert_eq!(ContainerAttrs::parse(&input.attrs).unwrap().bound, Some(expected));
+		let input: DeriveInput = parse_quote! {
+			#[type_metadata(bound = "")]
+			struct S;
+		};
+		assert_eq!(ContainerAttrs::parse(&input.attrs).unwrap().bound, Some(vec![]));
+		let input: DeriveInput = parse_quote! {
+			#[type_metadata(crate = "::facade::metadata")]
+			struct S;
+		};
+		let expected: Path = parse_quote!(::facade::metadata);
+		assert_eq!(ContainerAttrs::parse(&input.attrs).unwrap().krate, Some(expected));
+		let input: DeriveInput = parse_quote! {
+			#[type_metadata(serde, serde)]
+			struct S;
+		};
+		assert_eq!(
+			ContainerAttrs::parse(&input.attrs)
+				.err()
+				.map(|err| err.to_string())
+				.as_deref(),
+			Some("duplicate type_metadata attribute `serde`")
+		);
+	}
+
+	#[test]
+	fn variant_attrs() {
+		let index = |input: DeriveInput| match input.data {
+			Data::Enum(data) => VariantAttrs::parse(&data.variants[0].attrs)
+				.map(|attrs| attrs.index.map(|index| index.value()))
+				.map_err(|err| err.to_string()),
+			_ => unreachable!("only enums are parsed"),
+		};
+		assert_eq!(
+			index(parse_quote! { enum E { #[type_metadata(index = 7)] A(u8) } }),
+			Ok(Some(7))
+		);
+		assert_eq!(index(parse_quote! { enum E { A(u8) } }), Ok(None));
+		assert_eq!(
+			index(parse_quote! { enum E { #[type_metadata(index = 256)] A(u8) } }),
+			Err("expected `u8` integer literal".into())
+		);
+		assert_eq!(
+			index(parse_quote! { enum E { #[type_metadata(index = "7")] A(u8) } }),
+			Err("expected `u8` integer literal".into())
+		);
+		assert_eq!(
+			index(parse_quote! { enum E { #[type_metadata(skip)] A(u8) } }),
+			Err("unknown type_metadata attribute `skip`".into())
+		);
+	}
+
+	#[test]
+	fn field_attrs_errors() {
+		let error = |input| parse(input).err().map(|err| err.to_string());
+		assert_eq!(
+			error(parse_quote! { struct S(#[type_metadata(unknown)] u32); }).as_deref(),
+			Some("unknown type_metadata attribute `unknown`")
+		);
+		assert_eq!(
+			error(parse_quote! { struct S(#[type_metadata(skip, skip)] u32); }).as_deref(),
+			Some("duplicate type_metadata attribute `skip`")
+		);
+		assert_eq!(
+			error(parse_quote! { struct S(#[type_metadata(rename = 1)] u32); }).as_deref(),
+			Some("expected string literal")
+		);
+		assert_eq!(
+			error(parse_quote! { struct S(#[type_metadata = "skip"] u32); }).as_deref(),
+			Some("expected `#[type_metadata(...)]`")
+		);
+		assert!(error(parse_quote! { struct S(#[type_metadata(bound = "T")] u32); }).is_some());
+	}
+}
@@ -0,0 +1,282 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounds of the type parameters of derived impls.
+
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use quote::quote;
+use syn::{
+	parse_quote,
+	visit::{self, Visit},
+	Generics, Ident, Type, TypePath, WherePredicate,
+};
+
+/// Returns the generics with the predicates appended to their where clause.
+pub fn with_predicates<I>(generics: &Generics, predicates: I) -> Generics
+where
+	I: IntoIterator<Item = WherePredicate>,
+{
+	let mut generics = generics.clone();
+	generics.make_where_clause().predicates.extend(predicates);
+	generics
+}
+
+/// The type parameters and associated types that identify instantiations of a type.
+///
+/// Every type parameter and associated type is a type parameter of the derived `TypeId`,
+/// so that they line up with the generic parameter names of the derived `TypeDef`.
+pub struct Identifiers {
+	/// The type parameters in the order of their declaration.
+	pub params: Vec<Ident>,
+	/// The type parameters used by described fields outside of `PhantomData`.
+	pub used: Vec<Ident>,
+	/// The type parameters only used as type arguments of `PhantomData`.
+	pub phantom_params: Vec<Ident>,
+	/// The associated types such as `T::Balance` in the order of their occurrence.
+	pub associated: Vec<TypePath>,
+	/// The `PhantomData` types referring to type parameters.
+	pub phantoms: Vec<TypePath>,
+}
+
+impl Identifiers {
+	/// Returns all type parameters of the generics as used.
+	pub fn all_params(generics: &Generics) -> Self {
+		let params = generics
+			.type_params()
+			.map(|param| param.ident.clone())
+			.collect::<Vec<_>>();
+		Self {
+			used: params.clone(),
+			params,
+			phantom_params: Vec::new(),
+			associated: Vec::new(),
+			phantoms: Vec::new(),
+		}
+	}
+
+	/// Returns the type parameters and associated types used by the given types.
+	///
+	/// Associated types such as `T::Balance` are used as a whole instead of their type parameter
+	/// and the type arguments of `PhantomData` are ignored in favor of the `PhantomData` type.
+	pub fn infer<'a, I>(generics: &Generics, types: I) -> Self
+	where
+		I: IntoIterator<Item = &'a Type>,
+	{
+		let mut visitor = FindTypeParams {
+			params: generics.type_params().map(|param| &param.ident).collect(),
+			used: Vec::new(),
+			phantom_params: Vec::new(),
+			associated: Vec::new(),
+			phantoms: Vec::new(),
+		};
+		for ty in types {
+			visitor.visit_type(ty);
+		}
+		let declared = |used: &[&Ident]| {
+			generics
+				.type_params()
+				.map(|param| &param.ident)
+				.filter(|ident| used.contains(ident))
+				.cloned()
+				.collect::<Vec<_>>()
+		};
+		let used = declared(&visitor.used);
+		let phantom_params = declared(&visitor.phantom_params)
+			.into_iter()
+			.filter(|ident| !used.contains(ident))
+			.collect();
+		Self {
+			params: generics.type_params().map(|param| param.ident.clone()).collect(),
+			used,
+			phantom_params,
+			associated: visitor.associated,
+			phantoms: visitor.phantoms,
+		}
+	}
+
+	/// Returns the type parameters of the derived `TypeId`, type parameters first.
+	///
+	/// Type parameters only used by `PhantomData` are identified by their `PhantomData`
+	/// and the ones not used at all by `()`.
+	pub fn types(&self) -> Vec<Type> {
+		let params = self.params.iter().map(|ident| {
+			if self.used.contains(ident) {
+				parse_quote!(#ident)
+			} else if self.phantom_params.contains(ident) {
+				parse_quote!(::core::marker::PhantomData<#ident>)
+			} else {
+				parse_quote!(())
+			}
+		});
+		let associated = self.associated.iter().map(|ty| Type::Path(ty.clone()));
+		params.chain(associated).collect()
+	}
+
+	/// Returns the generic parameter names of the derived `TypeDef`, one for each of [`Identifiers::types`].
+	pub fn names(&self) -> Vec<String> {
+		let params = self.params.iter().map(|ident| ident.to_string());
+		let associated = self.associated.iter().map(|ty| {
+			quote!(#ty)
+				.to_string()
+				.replace(" :: ", "::")
+				.replace("< ", "<")
+				.replace(" >", ">")
+		});
+		params.chain(associated).collect()
+	}
+
+	/// Bounds the used type parameters, associated types and `PhantomData` types by `Metadata + 'static`.
+	pub fn predicates(&self) -> Vec<WherePredicate> {
+		let phantom_params = self
+			.phantom_params
+			.iter()
+			.map(|ident| -> Type { parse_quote!(::core::marker::PhantomData<#ident>) });
+		let phantoms = self.phantoms.iter().map(|ty| Type::Path(ty.clone()));
+		let used = self.used.iter().map(|ident| -> Type { parse_quote!(#ident) });
+		let associated = self.associated.iter().map(|ty| Type::Path(ty.clone()));
+		used.chain(associated)
+			.chain(phantoms)
+			.chain(phantom_params)
+			.map(|ty| parse_quote!(#ty: _type_metadata::Metadata + 'static))
+			.collect()
+	}
+}
+
+/// Collects the type parameters and associated types referred to by types.
+struct FindTypeParams<'a> {
+	params: Vec<&'a Ident>,
+	used: Vec<&'a Ident>,
+	phantom_params: Vec<&'a Ident>,
+	associated: Vec<TypePath>,
+	phantoms: Vec<TypePath>,
+}
+
+impl<'a> FindTypeParams<'a> {
+	/// Returns the type parameter that a path such as `T`, `T::X` or `<T as Trait>::X` starts with.
+	fn param_of(&self, ty: &TypePath) -> Option<&'a Ident> {
+		let path = match &ty.qself {
+			Some(qself) => match &*qself.ty {
+				Type::Path(TypePath { qself: None, path }) if path.segments.len() == 1 => path,
+				_ => return None,
+			},
+			None if ty.path.leading_colon.is_none() => &ty.path,
+			None => return None,
+		};
+		let first = &path.segments.first()?.value().ident;
+		self.params.iter().find(|param| **param == first).cloned()
+	}
+}
+
+impl<'a, 'ast> Visit<'ast> for FindTypeParams<'a> {
+	fn visit_type_path(&mut self, ty: &'ast TypePath) {
+		let is_phantom = ty.qself.is_none()
+			&& matches!(ty.path.segments.last(), Some(segment) if segment.value().ident == "PhantomData");
+		if is_phantom {
+			let mut visitor = FindTypeParams {
+				params: self.params.clone(),
+				used: Vec::new(),
+				phantom_params: Vec::new(),
+				associated: Vec::new(),
+				phantoms: Vec::new(),
+			};
+			visit::visit_type_path(&mut visitor, ty);
+			let is_generic = !visitor.used.is_empty() || !visitor.associated.is_empty();
+			if is_generic && !self.phantoms.contains(ty) {
+				self.phantoms.push(ty.clone());
+			}
+			for param in visitor.used.into_iter().chain(visitor.phantom_params) {
+				if !self.phantom_params.contains(&param) {
+					self.phantom_params.push(param);
+				}
+			}
+			return;
+		}
+		if let Some(param) = self.param_of(ty) {
+			if ty.qself.is_none() && ty.path.segments.len() == 1 {
+				if !self.used.contains(&param) {
+					self.used.push(param);
+				}
+			} else {
+				if !self.associated.contains(ty) {
+					self.associated.push(ty.clone());
+				}
+				return;
+			}
+		}
+		visit::visit_type_path(self, ty);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn predicates(generics: Generics, types: &[Type]) -> alloc::string::String {
+		let predicates = Identifiers::infer(&generics, types).predicates();
+		quote!(#( #predicates ),*).to_string()
+	}
+
+	#[test]
+	fn infer_bounds_of_used_params() {
+		let generics: Generics = parse_quote!(<T, U: Config, V, W>);
+		let types: Vec<Type> = vec![
+			parse_quote!(Vec<(T, W)>),
+			parse_quote!(Option<U::Balance>),
+			parse_quote!(U::Balance),
+			parse_quote!(<V as Config>::Hash),
+		];
+		let expected = quote! {
+			T: _type_metadata::Metadata + 'static,
+			W: _type_metadata::Metadata + 'static,
+			U::Balance: _type_metadata::Metadata + 'static,
+			<V as Config>::Hash: _type_metadata::Metadata + 'static
+		};
+		assert_eq!(predicates(generics, &types), expected.to_string());
+	}
+
+	#[test]
+	fn ignore_phantom_data() {
+		let generics: Generics = parse_quote!(<T, U>);
+		let types: Vec<Type> = vec![
+			parse_quote!(PhantomData<T>),
+			parse_quote!(core::marker::PhantomData<(T, U)>),
+			parse_quote!(Option<U>),
+		];
+		let expected = quote! {
+			U: _type_metadata::Metadata + 'static,
+			PhantomData<T>: _type_metadata::Metadata + 'static,
+			core::marker::PhantomData<(T, U)>: _type_metadata::Metadata + 'static,
+			::core::marker::PhantomData<T>: _type_metadata::Metadata + 'static
+		};
+		assert_eq!(predicates(generics, &types), expected.to_string());
+	}
+
+	#[test]
+	fn identify_all_params_and_associated_types() {
+		let generics: Generics = parse_quote!(<T, U: Config, V, W>);
+		let types: Vec<Type> = vec![parse_quote!(U::Balance), parse_quote!(PhantomData<V>), parse_quote!(T)];
+		let identifiers = Identifiers::infer(&generics, &types);
+		let types = identifiers.types();
+		let expected = quote! {
+			T, (), ::core::marker::PhantomData<V>, (), U::Balance
+		};
+		assert_eq!(quote!(#( #types ),*).to_string(), expected.to_string());
+		assert_eq!(identifiers.names(), ["T", "U", "V", "W", "U::Balance"]);
+	}
+}
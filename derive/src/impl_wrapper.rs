@@ -14,31 +14,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(not(feature = "std"))]
-use alloc::{
-	format,
-	string::{String, ToString},
-};
-
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Ident;
-
-pub fn wrap(ident: &Ident, trait_name: &'static str, impl_quote: TokenStream2) -> TokenStream2 {
-	let mut renamed = String::from(format!("_IMPL_{}_FOR_", trait_name));
-	renamed.push_str(ident.to_string().trim_start_matches("r#"));
-	let dummy_const = Ident::new(&renamed, Span::call_site());
+use syn::Path;
 
+/// Wraps a derived impl into an anonymous constant that imports the `type_metadata` crate
+/// as `_type_metadata`, either by its crate name or by the given path.
+pub fn wrap(impl_quote: TokenStream2, krate: Option<&Path>) -> TokenStream2 {
+	let krate = match krate {
+		Some(path) => quote! { #path },
+		None => quote! { type_metadata },
+	};
 	quote! {
-		#[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
-		const #dummy_const: () = {
+		#[allow(unused_attributes, unused_qualifications)]
+		const _: () = {
 			#[allow(unknown_lints)]
-			#[cfg_attr(feature = "cargo-clippy", allow(useless_attribute))]
+			#[allow(clippy::useless_attribute)]
 			#[allow(rust_2018_idioms)]
-			use type_metadata as _type_metadata;
-			#[cfg(not(feature = "std"))]
+			use #krate as _type_metadata;
 			extern crate alloc as _alloc;
-			#[cfg(not(feature = "std"))]
 			use _alloc::{vec, vec::Vec};
 			#impl_quote;
 		};
@@ -19,24 +19,27 @@
 extern crate alloc;
 extern crate proc_macro;
 
+mod attr;
+mod bound;
 mod impl_wrapper;
 mod metadata;
+mod serde_attr;
 mod type_def;
 mod type_id;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(TypeId)]
+#[proc_macro_derive(TypeId, attributes(type_metadata))]
 pub fn type_id(input: TokenStream) -> TokenStream {
 	type_id::generate(input.into()).into()
 }
 
-#[proc_macro_derive(TypeDef)]
+#[proc_macro_derive(TypeDef, attributes(type_metadata))]
 pub fn type_def(input: TokenStream) -> TokenStream {
 	type_def::generate(input.into()).into()
 }
 
-#[proc_macro_derive(Metadata)]
+#[proc_macro_derive(Metadata, attributes(type_metadata))]
 pub fn metadata(input: TokenStream) -> TokenStream {
 	metadata::generate(input.into()).into()
 }
@@ -0,0 +1,316 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interpretation of the `#[serde(...)]` attributes that affect the serde data model.
+//!
+//! Attributes are parsed leniently: unknown keys are ignored since they are validated by serde itself.
+
+use crate::attr::lit_str;
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use syn::{parse::Result, Attribute, Error, Ident, LitStr, Meta, NestedMeta};
+
+/// The serde attributes of a type.
+#[derive(Default)]
+pub struct SerdeContainer {
+	/// The serialized name of the type.
+	pub rename: Option<LitStr>,
+	/// Renames all fields of a struct or all variants of an enum.
+	pub rename_all: Option<RenameRule>,
+	/// Renames the fields of all struct variants of an enum.
+	pub rename_all_fields: Option<RenameRule>,
+	/// Serializes the type as its single field.
+	pub transparent: bool,
+	/// The tag of an internally or adjacently tagged enum or of a struct with named fields.
+	pub tag: Option<LitStr>,
+	/// The content of an adjacently tagged enum.
+	pub content: Option<LitStr>,
+	/// Serializes the variants of an enum without a tag.
+	pub untagged: bool,
+}
+
+impl SerdeContainer {
+	/// Parses the serde attributes of a type.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut container = Self::default();
+		for meta in serde_metas(attrs)? {
+			match meta.name().to_string().as_str() {
+				"rename" => container.rename = serialize_name(&meta)?.or(container.rename),
+				"rename_all" => container.rename_all = rename_rule(&meta)?.or(container.rename_all),
+				"rename_all_fields" => {
+					container.rename_all_fields = rename_rule(&meta)?.or(container.rename_all_fields)
+				}
+				"transparent" => container.transparent = true,
+				"tag" => container.tag = Some(serialize_name(&meta)?.ok_or_else(|| expected_name(&meta))?),
+				"content" => container.content = Some(serialize_name(&meta)?.ok_or_else(|| expected_name(&meta))?),
+				"untagged" => container.untagged = true,
+				_ => (),
+			}
+		}
+		Ok(container)
+	}
+}
+
+/// The serde attributes of an enum variant.
+#[derive(Default)]
+pub struct SerdeVariant {
+	/// The serialized name of the variant.
+	pub rename: Option<LitStr>,
+	/// Renames all fields of a struct variant.
+	pub rename_all: Option<RenameRule>,
+	/// Omits the variant from serialization.
+	pub skip: bool,
+}
+
+impl SerdeVariant {
+	/// Parses the serde attributes of an enum variant.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut variant = Self::default();
+		for meta in serde_metas(attrs)? {
+			match meta.name().to_string().as_str() {
+				"rename" => variant.rename = serialize_name(&meta)?.or(variant.rename),
+				"rename_all" => variant.rename_all = rename_rule(&meta)?.or(variant.rename_all),
+				"skip" | "skip_serializing" => variant.skip = true,
+				_ => (),
+			}
+		}
+		Ok(variant)
+	}
+}
+
+/// The serde attributes of a field.
+#[derive(Default)]
+pub struct SerdeField {
+	/// The serialized name of the field.
+	pub rename: Option<LitStr>,
+	/// Omits the field from serialization.
+	pub skip: bool,
+	/// Inlines the fields of the field type into the enclosing fields.
+	pub flatten: bool,
+}
+
+impl SerdeField {
+	/// Parses the serde attributes of a field.
+	pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut field = Self::default();
+		for meta in serde_metas(attrs)? {
+			match meta.name().to_string().as_str() {
+				"rename" => field.rename = serialize_name(&meta)?.or(field.rename),
+				"skip" | "skip_serializing" => field.skip = true,
+				"flatten" => field.flatten = true,
+				_ => (),
+			}
+		}
+		Ok(field)
+	}
+}
+
+/// The rules of `#[serde(rename_all = "...")]`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenameRule {
+	Lower,
+	Upper,
+	Pascal,
+	Camel,
+	Snake,
+	ScreamingSnake,
+	Kebab,
+	ScreamingKebab,
+}
+
+impl RenameRule {
+	fn parse(lit: &LitStr) -> Result<Self> {
+		match lit.value().as_str() {
+			"lowercase" => Ok(RenameRule::Lower),
+			"UPPERCASE" => Ok(RenameRule::Upper),
+			"PascalCase" => Ok(RenameRule::Pascal),
+			"camelCase" => Ok(RenameRule::Camel),
+			"snake_case" => Ok(RenameRule::Snake),
+			"SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+			"kebab-case" => Ok(RenameRule::Kebab),
+			"SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebab),
+			rule => Err(Error::new_spanned(lit, format!("unknown rename rule `{}`", rule))),
+		}
+	}
+
+	/// Applies the rule to a variant name which is expected in PascalCase.
+	pub fn apply_to_variant(self, variant: &str) -> String {
+		match self {
+			RenameRule::Pascal => String::from(variant),
+			RenameRule::Lower => variant.to_ascii_lowercase(),
+			RenameRule::Upper => variant.to_ascii_uppercase(),
+			RenameRule::Camel => lowercase_first(variant),
+			RenameRule::Snake => {
+				let mut snake = String::new();
+				for (i, ch) in variant.char_indices() {
+					if i > 0 && ch.is_uppercase() {
+						snake.push('_');
+					}
+					snake.push(ch.to_ascii_lowercase());
+				}
+				snake
+			}
+			RenameRule::ScreamingSnake => RenameRule::Snake.apply_to_variant(variant).to_ascii_uppercase(),
+			RenameRule::Kebab => RenameRule::Snake.apply_to_variant(variant).replace('_', "-"),
+			RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply_to_variant(variant).replace('_', "-"),
+		}
+	}
+
+	/// Applies the rule to a field name which is expected in snake_case.
+	pub fn apply_to_field(self, field: &str) -> String {
+		match self {
+			RenameRule::Lower | RenameRule::Snake => String::from(field),
+			RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+			RenameRule::Pascal => {
+				let mut pascal = String::new();
+				let mut capitalize = true;
+				for ch in field.chars() {
+					if ch == '_' {
+						capitalize = true;
+					} else if capitalize {
+						pascal.push(ch.to_ascii_uppercase());
+						capitalize = false;
+					} else {
+						pascal.push(ch);
+					}
+				}
+				pascal
+			}
+			RenameRule::Camel => lowercase_first(&RenameRule::Pascal.apply_to_field(field)),
+			RenameRule::Kebab => field.replace('_', "-"),
+			RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+		}
+	}
+}
+
+/// Returns the name of an identifier as seen by serde, i.e. without the `r#` prefix of raw identifiers.
+pub fn unraw(ident: &Ident) -> String {
+	let name = ident.to_string();
+	String::from(name.strip_prefix("r#").unwrap_or(&name))
+}
+
+fn lowercase_first(name: &str) -> String {
+	let mut chars = name.chars();
+	match chars.next() {
+		Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+		None => String::new(),
+	}
+}
+
+/// Returns the items of all `#[serde(...)]` attributes in order.
+fn serde_metas(attrs: &[Attribute]) -> Result<Vec<Meta>> {
+	let mut metas = Vec::new();
+	for attr in attrs {
+		if !attr.path.is_ident("serde") {
+			continue;
+		}
+		if let Meta::List(list) = attr.parse_meta()? {
+			metas.extend(list.nested.into_iter().filter_map(|nested| match nested {
+				NestedMeta::Meta(meta) => Some(meta),
+				NestedMeta::Literal(_) => None,
+			}));
+		}
+	}
+	Ok(metas)
+}
+
+/// Returns the serialization name of `key = "name"` or `key(serialize = "name")`.
+fn serialize_name(meta: &Meta) -> Result<Option<LitStr>> {
+	match meta {
+		Meta::NameValue(name_value) => lit_str(&name_value.lit).map(Some),
+		Meta::List(list) => {
+			for nested in &list.nested {
+				if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
+					if name_value.ident == "serialize" {
+						return lit_str(&name_value.lit).map(Some);
+					}
+				}
+			}
+			Ok(None)
+		}
+		Meta::Word(_) => Err(expected_name(meta)),
+	}
+}
+
+fn rename_rule(meta: &Meta) -> Result<Option<RenameRule>> {
+	serialize_name(meta)?.as_ref().map(RenameRule::parse).transpose()
+}
+
+fn expected_name(meta: &Meta) -> Error {
+	Error::new_spanned(meta, format!("expected `{} = \"...\"`", meta.name()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use syn::{parse_quote, DeriveInput};
+
+	#[test]
+	fn rename_rules() {
+		let rules = [
+			(RenameRule::Lower, "verylongvariant", "very_long_field"),
+			(RenameRule::Upper, "VERYLONGVARIANT", "VERY_LONG_FIELD"),
+			(RenameRule::Pascal, "VeryLongVariant", "VeryLongField"),
+			(RenameRule::Camel, "veryLongVariant", "veryLongField"),
+			(RenameRule::Snake, "very_long_variant", "very_long_field"),
+			(RenameRule::ScreamingSnake, "VERY_LONG_VARIANT", "VERY_LONG_FIELD"),
+			(RenameRule::Kebab, "very-long-variant", "very-long-field"),
+			(RenameRule::ScreamingKebab, "VERY-LONG-VARIANT", "VERY-LONG-FIELD"),
+		];
+		for (rule, variant, field) in rules.iter() {
+			assert_eq!(rule.apply_to_variant("VeryLongVariant"), *variant);
+			assert_eq!(rule.apply_to_field("very_long_field"), *field);
+		}
+	}
+
+	#[test]
+	fn container_attrs() {
+		let input: DeriveInput = parse_quote! {
+			#[derive(Serialize)]
+			#[serde(tag = "type", content = "value", deny_unknown_fields)]
+			#[serde(rename_all(serialize = "camelCase", deserialize = "snake_case"))]
+			#[serde(rename(serialize = "Entry"))]
+			enum E {}
+		};
+		let container = SerdeContainer::parse(&input.attrs).unwrap();
+		assert_eq!(container.rename.unwrap().value(), "Entry");
+		assert_eq!(container.rename_all, Some(RenameRule::Camel));
+		assert_eq!(container.tag.unwrap().value(), "type");
+		assert_eq!(container.content.unwrap().value(), "value");
+		assert!(!container.untagged && !container.transparent);
+
+		let input: DeriveInput = parse_quote! {
+			#[serde(rename_all = "Title Case")]
+			enum E {}
+		};
+		assert_eq!(
+			SerdeContainer::parse(&input.attrs)
+				.err()
+				.map(|err| err.to_string())
+				.as_deref(),
+			Some("unknown rename rule `Title Case`")
+		);
+	}
+
+	#[test]
+	fn raw_identifiers() {
+		assert_eq!(unraw(&parse_quote!(r#type)), "type");
+		assert_eq!(unraw(&parse_quote!(name)), "name");
+	}
+}
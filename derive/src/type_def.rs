@@ -14,80 +14,299 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::impl_wrapper::wrap;
+use crate::{
+	attr::{ContainerAttrs, FieldAttrs, VariantAttrs},
+	bound::{self, Identifiers},
+	impl_wrapper::wrap,
+	serde_attr::{unraw, RenameRule, SerdeContainer, SerdeField, SerdeVariant},
+};
+use alloc::{format, string::String, vec::Vec};
+use core::convert::TryFrom;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{
-	self, parse::Result, parse_quote, punctuated::Punctuated, token::Comma, Data, DataEnum, DataStruct, DataUnion,
-	DeriveInput, Expr, ExprLit, Field, Fields, Lit, Variant,
+	self, parse::Result, punctuated::Punctuated, token::Comma, Attribute, Data, DataEnum, DataStruct, DataUnion,
+	DeriveInput, Error, Field, Fields, GenericParam, Ident, Lit, LitStr, Meta, NestedMeta, Type, Variant,
+	WherePredicate,
 };
 
 pub fn generate(input: TokenStream2) -> TokenStream2 {
-	match generate_impl(input.into()) {
-		Ok(output) => output.into(),
-		Err(err) => err.to_compile_error().into(),
+	match generate_impl(input) {
+		Ok(output) => output,
+		Err(err) => err.to_compile_error(),
 	}
 }
 
+/// Generates the `HasTypeDef` impl.
+///
+/// The generated `generic_type` method refers to an instantiation of the type as a `Metadata`,
+/// so `HasTypeId` must be implemented for it as well, e.g. by deriving `TypeId` alongside.
 pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
-	let mut ast: DeriveInput = syn::parse2(input)?;
+	let ast: DeriveInput = syn::parse2(input)?;
+	let container_attrs = ContainerAttrs::parse(&ast.attrs)?;
+	let serde = if container_attrs.serde {
+		Some(SerdeContainer::parse(&ast.attrs)?)
+	} else {
+		None
+	};
 
-	ast.generics.type_params_mut().for_each(|p| {
-		p.bounds.push(parse_quote!(_type_metadata::Metadata));
-		p.bounds.push(parse_quote!('static));
-	});
+	let identifiers = identifiers(&ast, &container_attrs)?;
+	let (predicates, custom_bounds) = match container_attrs.bound {
+		Some(bound) => (bound, true),
+		None => field_predicates(&ast, serde.as_ref())?,
+	};
+	let generic_type = if custom_bounds {
+		quote! {}
+	} else {
+		generate_generic_type(&ast, &identifiers)
+	};
+	let generics = bound::with_predicates(&ast.generics, predicates);
 
 	let ident = &ast.ident;
-	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	let generic_params = identifiers.names();
+	let docs = generate_docs(&ast.attrs);
 
 	let def = match &ast.data {
-		Data::Struct(ref s) => generate_struct_def(s),
-		Data::Enum(ref e) => generate_enum_def(e),
-		Data::Union(ref u) => generate_union_def(u),
+		Data::Struct(ref s) => generate_struct_def(ident, s, serde.as_ref())?,
+		Data::Enum(ref e) => generate_enum_def(e, &ast.attrs, serde.as_ref())?,
+		Data::Union(ref u) => generate_union_def(u)?,
 	};
 
 	let has_type_def_impl = quote! {
 		impl #impl_generics _type_metadata::HasTypeDef for #ident #ty_generics #where_clause {
 			fn type_def() -> _type_metadata::TypeDef {
-				#def.into()
+				_type_metadata::TypeDef::new(vec![#( #generic_params, )*], #def) #docs
 			}
+
+			#generic_type
 		}
 	};
 
-	Ok(wrap(ident, "HAS_TYPE_DEF", has_type_def_impl).into())
+	Ok(wrap(has_type_def_impl, container_attrs.krate.as_ref()))
+}
+
+/// Returns the types identifying instantiations of the type.
+///
+/// These are all type parameters if the type has a `bound` attribute
+/// and otherwise the ones inferred from its described fields.
+pub fn identifiers(ast: &DeriveInput, container_attrs: &ContainerAttrs) -> Result<Identifiers> {
+	if container_attrs.bound.is_some() {
+		return Ok(Identifiers::all_params(&ast.generics));
+	}
+	let serde = if container_attrs.serde {
+		Some(SerdeContainer::parse(&ast.attrs)?)
+	} else {
+		None
+	};
+	let types = described_fields(ast, serde.as_ref())?
+		.into_iter()
+		.map(|(ty, _)| ty)
+		.collect::<Vec<_>>();
+	Ok(Identifiers::infer(&ast.generics, &types))
+}
+
+/// Returns the where clause predicates required by the described fields
+/// and whether any of them are given by `bound` attributes.
+///
+/// Only the type parameters used by fields are bounded, so that parameters which are
+/// skipped or only used through associated types are not required to implement `Metadata`.
+fn field_predicates(ast: &DeriveInput, serde: Option<&SerdeContainer>) -> Result<(Vec<WherePredicate>, bool)> {
+	let mut predicates = Vec::new();
+	let mut custom_bounds = false;
+	let mut types = Vec::new();
+	for (ty, bound) in described_fields(ast, serde)? {
+		match bound {
+			Some(bound) => {
+				predicates.extend(bound);
+				custom_bounds = true;
+			}
+			None => types.push(ty),
+		}
+	}
+	predicates.extend(Identifiers::infer(&ast.generics, &types).predicates());
+	Ok((predicates, custom_bounds))
+}
+
+/// Returns the described types of the fields that are not skipped together with their `bound` attributes.
+fn described_fields(
+	ast: &DeriveInput,
+	serde: Option<&SerdeContainer>,
+) -> Result<Vec<(Type, Option<Vec<WherePredicate>>)>> {
+	let fields: Vec<&Field> = match &ast.data {
+		Data::Struct(data_struct) => data_struct.fields.iter().collect(),
+		Data::Enum(data_enum) => {
+			let mut fields = Vec::new();
+			for variant in &data_enum.variants {
+				if serde.is_none() || !SerdeVariant::parse(&variant.attrs)?.skip {
+					fields.extend(variant.fields.iter());
+				}
+			}
+			fields
+		}
+		Data::Union(data_union) => data_union.fields.named.iter().collect(),
+	};
+	let mut described = Vec::new();
+	for field in fields {
+		let attrs = FieldAttrs::parse(&field.attrs)?;
+		if attrs.skip || (serde.is_some() && SerdeField::parse(&field.attrs)?.skip) {
+			continue;
+		}
+		described.push((attrs.with.unwrap_or_else(|| field.ty.clone()), attrs.bound));
+	}
+	Ok(described)
+}
+
+/// Generates the `generic_type` method for generic types.
+///
+/// Generic types are instantiated with `GenericParameter` markers at the positions of their
+/// type parameters used by fields, directly or through `PhantomData`, which only satisfy the
+/// `Metadata` bounds, while the unused type parameters are instantiated with `()`. Thus nothing
+/// is generated for types without used type parameters or whose generic parameters have
+/// further bounds, which includes types with associated types and `bound` attributes.
+fn generate_generic_type(ast: &DeriveInput, identifiers: &Identifiers) -> TokenStream2 {
+	let generics = &ast.generics;
+	let is_unbounded = generics.where_clause.is_none()
+		&& generics
+			.params
+			.iter()
+			.all(|param| matches!(param, GenericParam::Type(ty) if ty.bounds.is_empty()));
+	let is_used = |ident: &Ident| identifiers.used.contains(ident) || identifiers.phantom_params.contains(ident);
+	if !identifiers.params.iter().any(is_used) || !is_unbounded {
+		return quote! {};
+	}
+	let ident = &ast.ident;
+	let markers = identifiers.params.iter().enumerate().map(|(index, param)| {
+		if is_used(param) {
+			let index = index as u16;
+			quote! {
+				_type_metadata::GenericParameter<#index>
+			}
+		} else {
+			quote! { () }
+		}
+	});
+	quote! {
+		fn generic_type() -> Option<_type_metadata::MetaType> {
+			Some(_type_metadata::MetaType::new::<#ident<#( #markers ),*>>())
+		}
+	}
+}
+
+/// Generates the `with_docs` call that attaches the `///` doc comments of an item.
+///
+/// Nothing is generated if the item has no doc comments or the `docs` feature is disabled.
+fn generate_docs(attrs: &[Attribute]) -> TokenStream2 {
+	if !cfg!(feature = "docs") {
+		return quote! {};
+	}
+	let docs = attrs
+		.iter()
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::NameValue(ref meta)) if meta.ident == "doc" => match &meta.lit {
+				Lit::Str(lit) => Some(lit.value()),
+				_ => None,
+			},
+			_ => None,
+		})
+		.map(|doc| String::from(doc.strip_prefix(' ').unwrap_or(&doc)))
+		.collect::<Vec<_>>();
+	if docs.is_empty() {
+		return quote! {};
+	}
+	quote! {
+		.with_docs(&[#( #docs ),*])
+	}
+}
+
+/// Whether definitions describe the Rust type or its serde data model.
+#[derive(Clone, Copy)]
+enum Mode {
+	Rust,
+	/// Fields are named by serde, falling back to the `rename_all` rule of the enclosing item.
+	Serde {
+		rename_all: Option<RenameRule>,
+	},
 }
 
 type FieldsList = Punctuated<Field, Comma>;
 
-fn generate_fields_def(fields: &FieldsList) -> TokenStream2 {
-	let fields_def = fields.iter().map(|f| {
-		let (ty, ident) = (&f.ty, &f.ident);
+fn generate_fields_def(fields: &FieldsList, mode: Mode) -> Result<TokenStream2> {
+	let mut fields_def = Vec::new();
+	for f in fields {
+		let attrs = FieldAttrs::parse(&f.attrs)?;
+		let serde_attrs = match mode {
+			Mode::Rust => SerdeField::default(),
+			Mode::Serde { .. } => SerdeField::parse(&f.attrs)?,
+		};
+		if attrs.skip || serde_attrs.skip {
+			continue;
+		}
+		let ty = attrs.with.as_ref().unwrap_or(&f.ty);
+		let docs = generate_docs(&f.attrs);
+		let flatten = if serde_attrs.flatten {
+			quote! { .flattened() }
+		} else {
+			quote! {}
+		};
 		let meta_type = quote! {
 			<#ty as _type_metadata::Metadata>::meta_type()
 		};
-		if let Some(i) = ident {
-			quote! {
-				_type_metadata::NamedField::new(stringify!(#i), #meta_type)
+		let field_def = match (&f.ident, attrs.rename) {
+			(Some(_), Some(rename)) => quote! {
+				_type_metadata::NamedField::new(#rename, #meta_type) #flatten #docs
+			},
+			(Some(i), None) => {
+				let name = match (mode, serde_attrs.rename) {
+					(Mode::Rust, _) => quote! { stringify!(#i) },
+					(Mode::Serde { .. }, Some(rename)) => quote! { #rename },
+					(Mode::Serde { rename_all }, None) => {
+						let name = unraw(i);
+						let name = rename_all.map_or(name.clone(), |rule| rule.apply_to_field(&name));
+						quote! { #name }
+					}
+				};
+				quote! {
+					_type_metadata::NamedField::new(#name, #meta_type) #flatten #docs
+				}
 			}
-		} else {
-			quote! {
-				_type_metadata::UnnamedField::new(#meta_type)
+			(None, Some(rename)) => {
+				return Err(Error::new_spanned(rename, "`rename` is only supported on named fields"));
 			}
-		}
-	});
-	quote! { vec![#( #fields_def, )*] }
+			(None, None) => quote! {
+				_type_metadata::UnnamedField::new(#meta_type) #docs
+			},
+		};
+		fields_def.push(field_def);
+	}
+	Ok(quote! { vec![#( #fields_def, )*] })
 }
 
-fn generate_struct_def(data_struct: &DataStruct) -> TokenStream2 {
-	match data_struct.fields {
+fn generate_struct_def(
+	ident: &Ident,
+	data_struct: &DataStruct,
+	serde: Option<&SerdeContainer>,
+) -> Result<TokenStream2> {
+	if let Some(serde) = serde {
+		if let Some(tag) = &serde.tag {
+			return generate_tagged_struct_def(ident, data_struct, serde, tag);
+		}
+		if serde.transparent {
+			return generate_transparent_def(&data_struct.fields);
+		}
+	}
+	let mode = serde.map_or(Mode::Rust, |serde| Mode::Serde {
+		rename_all: serde.rename_all,
+	});
+	let def = match data_struct.fields {
 		Fields::Named(ref fs) => {
-			let fields = generate_fields_def(&fs.named);
+			let fields = generate_fields_def(&fs.named, mode)?;
 			quote! {
 				_type_metadata::TypeDefStruct::new(#fields)
 			}
 		}
 		Fields::Unnamed(ref fs) => {
-			let fields = generate_fields_def(&fs.unnamed);
+			let fields = generate_fields_def(&fs.unnamed, mode)?;
 			quote! {
 				_type_metadata::TypeDefTupleStruct::new(#fields)
 			}
@@ -95,77 +314,279 @@ fn generate_struct_def(data_struct: &DataStruct) -> TokenStream2 {
 		Fields::Unit => quote! {
 			_type_metadata::TypeDefTupleStruct::unit()
 		},
+	};
+	Ok(def)
+}
+
+/// Generates the definition of a `#[serde(tag = "...")]` struct.
+///
+/// Serde prepends the tag with the name of the struct to its fields, which is described
+/// as an internally tagged enum with a single struct variant named after the struct.
+fn generate_tagged_struct_def(
+	ident: &Ident,
+	data_struct: &DataStruct,
+	serde: &SerdeContainer,
+	tag: &LitStr,
+) -> Result<TokenStream2> {
+	let fields = match &data_struct.fields {
+		Fields::Named(fs) => &fs.named,
+		_ => {
+			return Err(Error::new_spanned(
+				tag,
+				"`#[serde(tag = \"...\")]` is only supported on enums and structs with named fields",
+			))
+		}
+	};
+	let name = match &serde.rename {
+		Some(rename) => quote! { #rename },
+		None => {
+			let name = unraw(ident);
+			quote! { #name }
+		}
+	};
+	let fields = generate_fields_def(
+		fields,
+		Mode::Serde {
+			rename_all: serde.rename_all,
+		},
+	)?;
+	Ok(quote! {
+		_type_metadata::TypeDefEnum::new(vec![
+			_type_metadata::EnumVariantStruct::new(#name, #fields).into(),
+		])
+		.with_tagging(_type_metadata::EnumTagging::Internal { tag: #tag })
+	})
+}
+
+/// Generates the definition of a `#[serde(transparent)]` struct as a tuple struct of its single field.
+fn generate_transparent_def(fields: &Fields) -> Result<TokenStream2> {
+	let mut remaining = Vec::new();
+	for f in fields {
+		let attrs = FieldAttrs::parse(&f.attrs)?;
+		if !attrs.skip && !SerdeField::parse(&f.attrs)?.skip {
+			remaining.push((attrs.with.unwrap_or_else(|| f.ty.clone()), generate_docs(&f.attrs)));
+		}
+	}
+	match remaining.as_slice() {
+		[(ty, docs)] => Ok(quote! {
+			_type_metadata::TypeDefTupleStruct::new(vec![
+				_type_metadata::UnnamedField::new(<#ty as _type_metadata::Metadata>::meta_type()) #docs
+			])
+		}),
+		_ => Err(Error::new_spanned(
+			fields,
+			"`#[serde(transparent)]` requires exactly one field that is not skipped",
+		)),
 	}
 }
 
 type VariantList = Punctuated<Variant, Comma>;
 
-fn generate_c_like_enum_def(variants: &VariantList) -> TokenStream2 {
-	let variants_def = variants.into_iter().enumerate().map(|(i, v)| {
-		let name = &v.ident;
-		let discriminant = if let Some((
-			_,
-			Expr::Lit(ExprLit {
-				lit: Lit::Int(lit_int), ..
-			}),
-		)) = &v.discriminant
-		{
-			lit_int.value()
-		} else {
-			i as u64
-		};
-		quote! {
-			_type_metadata::ClikeEnumVariant::new(stringify!(#name), #discriminant)
+/// Returns the name of a variant and the mode of its fields or `None` if serde skips the variant.
+fn variant_name(variant: &Variant, serde: Option<&SerdeContainer>) -> Result<Option<(TokenStream2, Mode)>> {
+	let ident = &variant.ident;
+	let serde = match serde {
+		Some(serde) => serde,
+		None => return Ok(Some((quote! { stringify!(#ident) }, Mode::Rust))),
+	};
+	let serde_variant = SerdeVariant::parse(&variant.attrs)?;
+	if serde_variant.skip {
+		return Ok(None);
+	}
+	let name = match serde_variant.rename {
+		Some(rename) => quote! { #rename },
+		None => {
+			let name = unraw(ident);
+			let name = serde
+				.rename_all
+				.map_or(name.clone(), |rule| rule.apply_to_variant(&name));
+			quote! { #name }
 		}
-	});
-	quote! {
-		_type_metadata::TypeDefClikeEnum::new(vec![#( #variants_def, )*])
+	};
+	let mode = Mode::Serde {
+		rename_all: serde_variant.rename_all.or(serde.rename_all_fields),
+	};
+	Ok(Some((name, mode)))
+}
+
+/// Generates the definition of an enum whose variants are all unit variants.
+///
+/// The discriminants are evaluated by the compiler by casting the variants, which
+/// accounts for implicit increments and constant expressions. Casting to `i128` is
+/// lossless together with the `#[repr]` type which is recorded for all integer reprs.
+fn generate_c_like_enum_def(
+	variants: &VariantList,
+	attrs: &[Attribute],
+	serde: Option<&SerdeContainer>,
+) -> Result<TokenStream2> {
+	let mut variants_def = Vec::new();
+	for v in variants {
+		if let Some(index) = VariantAttrs::parse(&v.attrs)?.index {
+			return Err(Error::new_spanned(
+				index,
+				"`index` is not supported by enums without fields, which are encoded by their discriminants",
+			));
+		}
+		let name = match variant_name(v, serde)? {
+			Some((name, _)) => name,
+			None => continue,
+		};
+		let ident = &v.ident;
+		let docs = generate_docs(&v.attrs);
+		variants_def.push(quote! {
+			_type_metadata::ClikeEnumVariant::new(#name, Self::#ident as i128) #docs
+		});
 	}
+	let repr = generate_repr(attrs);
+	Ok(quote! {
+		_type_metadata::TypeDefClikeEnum::new(vec![#( #variants_def, )*]) #repr
+	})
+}
+
+/// Generates the `with_repr` call for enums with an integer `#[repr]`.
+fn generate_repr(attrs: &[Attribute]) -> Option<TokenStream2> {
+	const INTEGERS: &[&str] = &[
+		"u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+	];
+	attrs
+		.iter()
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(Meta::List(ref list)) if list.ident == "repr" => Some(list.nested.clone()),
+			_ => None,
+		})
+		.flatten()
+		.find_map(|nested| match nested {
+			NestedMeta::Meta(Meta::Word(ident)) if INTEGERS.iter().any(|integer| ident == integer) => {
+				Some(quote! { .with_repr::<#ident>() })
+			}
+			_ => None,
+		})
 }
 
 fn is_c_like_enum(variants: &VariantList) -> bool {
-	// any variant has an explicit discriminant
-	variants.iter().any(|v| v.discriminant.is_some()) ||
-	// all variants are unit
 	variants.iter().all(|v| v.fields == Fields::Unit)
 }
 
-fn generate_enum_def(data_enum: &DataEnum) -> TokenStream2 {
+/// Generates the `with_tagging` call for enums that are not externally tagged.
+fn generate_tagging(serde: &SerdeContainer) -> Option<TokenStream2> {
+	let tagging = match (serde.untagged, &serde.tag, &serde.content) {
+		(true, _, _) => quote! { _type_metadata::EnumTagging::Untagged },
+		(false, Some(tag), Some(content)) => quote! {
+			_type_metadata::EnumTagging::Adjacent { tag: #tag, content: #content }
+		},
+		(false, Some(tag), None) => quote! {
+			_type_metadata::EnumTagging::Internal { tag: #tag }
+		},
+		(false, None, _) => return None,
+	};
+	Some(quote! { .with_tagging(#tagging) })
+}
+
+fn generate_enum_def(
+	data_enum: &DataEnum,
+	attrs: &[Attribute],
+	serde: Option<&SerdeContainer>,
+) -> Result<TokenStream2> {
 	let variants = &data_enum.variants;
+	let tagging = serde.and_then(generate_tagging);
 
-	if is_c_like_enum(&variants) {
-		return generate_c_like_enum_def(variants);
+	// Only externally tagged enums serialize unit variants as their names.
+	if is_c_like_enum(variants) && tagging.is_none() {
+		return generate_c_like_enum_def(variants, attrs, serde);
 	}
 
-	let variants_def = variants.into_iter().map(|v| {
-		let ident = &v.ident;
-		let v_name = quote! {stringify!(#ident) };
-		match v.fields {
+	let mut variants_def = Vec::new();
+	let mut indices = Vec::new();
+	for (position, v) in variants.iter().enumerate() {
+		// Skipped variants keep their index so that the indices of the following ones are stable.
+		let index = variant_index(v, position)?;
+		if indices.contains(&index) {
+			return Err(Error::new_spanned(
+				&v.ident,
+				format!("duplicate variant index `{}`", index),
+			));
+		}
+		indices.push(index);
+		let (v_name, mode) = match variant_name(v, serde)? {
+			Some(name_and_mode) => name_and_mode,
+			None => continue,
+		};
+		let docs = generate_docs(&v.attrs);
+		let variant_def = match v.fields {
 			Fields::Named(ref fs) => {
-				let fields = generate_fields_def(&fs.named);
+				let fields = generate_fields_def(&fs.named, mode)?;
 				quote! {
-					_type_metadata::EnumVariantStruct::new(#v_name, #fields).into()
+					_type_metadata::EnumVariantStruct::new(#v_name, #fields) #docs .with_index(#index).into()
 				}
 			}
 			Fields::Unnamed(ref fs) => {
-				let fields = generate_fields_def(&fs.unnamed);
+				let fields = generate_fields_def(&fs.unnamed, mode)?;
 				quote! {
-					_type_metadata::EnumVariantTupleStruct::new(#v_name, #fields).into()
+					_type_metadata::EnumVariantTupleStruct::new(#v_name, #fields) #docs .with_index(#index).into()
 				}
 			}
 			Fields::Unit => quote! {
-				_type_metadata::EnumVariantUnit::new(#v_name).into()
+				_type_metadata::EnumVariantUnit::new(#v_name) #docs .with_index(#index).into()
 			},
-		}
-	});
-	quote! {
-		_type_metadata::TypeDefEnum::new(vec![#( #variants_def, )*])
+		};
+		variants_def.push(variant_def);
 	}
+	Ok(quote! {
+		_type_metadata::TypeDefEnum::new(vec![#( #variants_def, )*]) #tagging
+	})
 }
 
-fn generate_union_def(data_union: &DataUnion) -> TokenStream2 {
-	let fields = generate_fields_def(&data_union.fields.named);
-	quote! {
+/// Returns the index of a variant given by `#[type_metadata(index = n)]` or else its position.
+fn variant_index(variant: &Variant, position: usize) -> Result<u8> {
+	match VariantAttrs::parse(&variant.attrs)?.index {
+		Some(index) => Ok(index.value() as u8),
+		None => u8::try_from(position).map_err(|_| {
+			Error::new_spanned(
+				&variant.ident,
+				"variant position exceeds `u8`, specify `#[type_metadata(index = n)]`",
+			)
+		}),
+	}
+}
+
+fn generate_union_def(data_union: &DataUnion) -> Result<TokenStream2> {
+	let fields = generate_fields_def(&data_union.fields.named, Mode::Rust)?;
+	Ok(quote! {
 		_type_metadata::TypeDefUnion::new(#fields)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::string::ToString;
+
+	fn error(input: TokenStream2) -> Option<String> {
+		generate_impl(input).err().map(|err| err.to_string())
+	}
+
+	#[test]
+	fn tagged_struct_errors() {
+		assert_eq!(
+			error(quote! { #[type_metadata(serde)] #[serde(tag = "type")] struct S(u8); }).as_deref(),
+			Some("`#[serde(tag = \"...\")]` is only supported on enums and structs with named fields")
+		);
+		assert_eq!(
+			error(quote! { #[type_metadata(serde)] #[serde(tag = "type")] struct S { a: u8 } }),
+			None
+		);
+	}
+
+	#[test]
+	fn variant_index_errors() {
+		assert_eq!(
+			error(quote! { enum E { A(u8), #[type_metadata(index = 0)] B } }).as_deref(),
+			Some("duplicate variant index `0`")
+		);
+		assert_eq!(
+			error(quote! { enum E { #[type_metadata(index = 1)] A, B } }).as_deref(),
+			Some("`index` is not supported by enums without fields, which are encoded by their discriminants")
+		);
+		assert_eq!(error(quote! { enum E { #[type_metadata(index = 2)] A(u8), B } }), None);
 	}
 }
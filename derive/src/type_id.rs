@@ -14,32 +14,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::impl_wrapper::wrap;
+use crate::{attr::ContainerAttrs, bound, impl_wrapper::wrap, type_def};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{self, parse::Result, parse_quote, DeriveInput};
+use syn::{self, parse::Result, DeriveInput};
 
 pub fn generate(input: TokenStream2) -> TokenStream2 {
-	match generate_impl(input.into()) {
-		Ok(output) => output.into(),
-		Err(err) => err.to_compile_error().into(),
+	match generate_impl(input) {
+		Ok(output) => output,
+		Err(err) => err.to_compile_error(),
 	}
 }
 
 pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
-	let mut ast: DeriveInput = syn::parse2(input)?;
+	let ast: DeriveInput = syn::parse2(input)?;
 
-	ast.generics.type_params_mut().for_each(|p| {
-		p.bounds.push(parse_quote!(_type_metadata::Metadata));
-		p.bounds.push(parse_quote!('static));
-	});
+	let container_attrs = ContainerAttrs::parse(&ast.attrs)?;
+	// Type parameters that the definition does not depend on are identified by `()`,
+	// so that instantiations with equal identifiers have equal definitions.
+	let identifiers = type_def::identifiers(&ast, &container_attrs)?;
+	let predicates = match container_attrs.bound {
+		Some(bound) => bound,
+		None => identifiers.predicates(),
+	};
+	let generics = bound::with_predicates(&ast.generics, predicates);
 
 	let ident = &ast.ident;
-	let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-	let generic_type_ids = ast.generics.type_params().into_iter().map(|ty| {
-		let ty_ident = &ty.ident;
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	let generic_type_ids = identifiers.types().into_iter().map(|ty| {
 		quote! {
-			<#ty_ident as _type_metadata::Metadata>::meta_type()
+			<#ty as _type_metadata::Metadata>::meta_type()
 		}
 	});
 	let has_type_id_impl = quote! {
@@ -55,5 +59,5 @@ pub fn generate_impl(input: TokenStream2) -> Result<TokenStream2> {
 		}
 	};
 
-	Ok(wrap(ident, "HAS_TYPE_ID", has_type_id_impl).into())
+	Ok(wrap(has_type_id_impl, container_attrs.krate.as_ref()))
 }
@@ -0,0 +1,749 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compatibility checks between two versions of a registry.
+//!
+//! Custom types of both registries are matched by their type path made up of
+//! their namespace, name and type parameters. The definitions of matching types
+//! are compared field by field and variant by variant.
+//!
+//! A change is backwards-compatible if values encoded according to the old
+//! registry are decoded identically according to the new registry.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, EnumVariant, NamedField, OwnedRegistry, TypeDef, TypeDefKind, TypeId,
+	TypeIdPrimitive, UnnamedField,
+};
+
+/// Whether a change breaks decoding of values encoded according to the old registry.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Compatibility {
+	/// Values encoded according to the old registry are decoded identically.
+	Compatible,
+	/// Values encoded according to the old registry can no longer be decoded
+	/// or are decoded differently.
+	Breaking,
+}
+
+/// A change between two versions of a type.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Change {
+	/// The path of the changed type.
+	///
+	/// Changes to the fields of enum variants have the variant name appended.
+	pub path: String,
+	/// The kind of change.
+	pub kind: ChangeKind,
+}
+
+impl Change {
+	/// Returns whether the change is backwards-compatible.
+	pub fn compatibility(&self) -> Compatibility {
+		self.kind.compatibility()
+	}
+}
+
+/// The kinds of changes between two versions of a type.
+///
+/// Fields of tuple structs and tuple struct variants are named by their position.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ChangeKind {
+	/// The type has been added.
+	TypeAdded,
+	/// The type has been removed.
+	TypeRemoved,
+	/// The kind of the definition has changed, e.g. from struct to enum.
+	DefinitionChanged,
+	/// A field has been added.
+	FieldAdded {
+		/// The name of the added field.
+		field: String,
+	},
+	/// A field has been removed.
+	FieldRemoved {
+		/// The name of the removed field.
+		field: String,
+	},
+	/// Fields present in both versions appear in a different order.
+	FieldsReordered,
+	/// The type of a field has changed.
+	FieldTypeChanged {
+		/// The name of the changed field.
+		field: String,
+		/// The path of the old field type.
+		old: String,
+		/// The path of the new field type.
+		new: String,
+	},
+	/// A variant has been added.
+	VariantAdded {
+		/// The name of the added variant.
+		variant: String,
+	},
+	/// A variant has been removed.
+	VariantRemoved {
+		/// The name of the removed variant.
+		variant: String,
+	},
+	/// A variant has changed from a unit, struct or tuple struct variant into another one.
+	VariantKindChanged {
+		/// The name of the changed variant.
+		variant: String,
+	},
+	/// The index by which an enum variant is encoded has changed.
+	IndexChanged {
+		/// The name of the changed variant.
+		variant: String,
+		/// The old index.
+		old: usize,
+		/// The new index.
+		new: usize,
+	},
+	/// The discriminant of a C-like enum variant has changed.
+	DiscriminantChanged {
+		/// The name of the changed variant.
+		variant: String,
+		/// The old discriminant.
+		old: i128,
+		/// The new discriminant.
+		new: i128,
+	},
+	/// The integer type by which the discriminants of a C-like enum are encoded has changed.
+	ReprChanged {
+		/// The old `#[repr]` type.
+		old: Option<TypeIdPrimitive>,
+		/// The new `#[repr]` type.
+		new: Option<TypeIdPrimitive>,
+	},
+}
+
+impl ChangeKind {
+	/// Returns whether the kind of change is backwards-compatible.
+	pub fn compatibility(&self) -> Compatibility {
+		match self {
+			ChangeKind::TypeAdded | ChangeKind::VariantAdded { .. } => Compatibility::Compatible,
+			_ => Compatibility::Breaking,
+		}
+	}
+}
+
+/// The changes between two versions of a registry.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Report {
+	changes: Vec<Change>,
+}
+
+impl Report {
+	/// Returns all changes ordered by the path of the changed type.
+	pub fn changes(&self) -> &[Change] {
+		&self.changes
+	}
+
+	/// Returns all breaking changes.
+	pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+		self.changes
+			.iter()
+			.filter(|change| change.compatibility() == Compatibility::Breaking)
+	}
+
+	/// Returns `true` if all changes are backwards-compatible.
+	pub fn is_compatible(&self) -> bool {
+		self.breaking_changes().next().is_none()
+	}
+}
+
+/// Compares the custom types of the old and the new registry.
+pub fn compare(old: &OwnedRegistry, new: &OwnedRegistry) -> Report {
+	let old_types = custom_types(old);
+	let new_types = custom_types(new);
+	let mut comparison = Comparison {
+		old,
+		new,
+		changes: Vec::new(),
+	};
+	for (path, old_def) in &old_types {
+		match new_types.get(path) {
+			Some(new_def) => comparison.compare_defs(path, old_def, new_def),
+			None => comparison.push(path, ChangeKind::TypeRemoved),
+		}
+	}
+	for path in new_types.keys().filter(|path| !old_types.contains_key(*path)) {
+		comparison.push(path, ChangeKind::TypeAdded);
+	}
+	let mut changes = comparison.changes;
+	changes.sort_by(|lhs, rhs| lhs.path.cmp(&rhs.path));
+	Report { changes }
+}
+
+/// Returns the definitions of all custom types of the registry by their type paths.
+fn custom_types(registry: &OwnedRegistry) -> BTreeMap<String, &TypeDef<CompactForm>> {
+	registry
+		.enumerate()
+		.filter(|(_, id, _)| matches!(id, TypeId::Custom(_)))
+		.map(|(symbol, _, def)| {
+			let path = registry
+				.type_path(symbol)
+				.expect("enumerated symbols belong to the registry");
+			(path, def)
+		})
+		.collect()
+}
+
+/// Collects the changes between the definitions of two registries.
+struct Comparison<'a> {
+	old: &'a OwnedRegistry,
+	new: &'a OwnedRegistry,
+	changes: Vec<Change>,
+}
+
+impl<'a> Comparison<'a> {
+	fn push(&mut self, path: &str, kind: ChangeKind) {
+		self.changes.push(Change {
+			path: String::from(path),
+			kind,
+		});
+	}
+
+	fn compare_defs(&mut self, path: &str, old: &TypeDef<CompactForm>, new: &TypeDef<CompactForm>) {
+		match (old.kind(), new.kind()) {
+			(TypeDefKind::Builtin, TypeDefKind::Builtin) => (),
+			(TypeDefKind::Struct(old), TypeDefKind::Struct(new)) => {
+				self.compare_named_fields(path, old.fields(), new.fields())
+			}
+			(TypeDefKind::TupleStruct(old), TypeDefKind::TupleStruct(new)) => {
+				self.compare_unnamed_fields(path, old.fields(), new.fields())
+			}
+			(TypeDefKind::Union(old), TypeDefKind::Union(new)) => {
+				self.compare_named_fields(path, old.fields(), new.fields())
+			}
+			(TypeDefKind::ClikeEnum(old), TypeDefKind::ClikeEnum(new)) => {
+				if old.discriminant_layout() != new.discriminant_layout() {
+					self.push(
+						path,
+						ChangeKind::ReprChanged {
+							old: old.repr().cloned(),
+							new: new.repr().cloned(),
+						},
+					);
+				}
+				let old_variants = old
+					.variants()
+					.iter()
+					.map(|variant| (self.old_string(variant.name()), variant.discriminant()))
+					.collect::<Vec<_>>();
+				let new_variants = new
+					.variants()
+					.iter()
+					.map(|variant| (self.new_string(variant.name()), variant.discriminant()))
+					.collect::<Vec<_>>();
+				// Variants are encoded by their discriminants and thus may be reordered freely.
+				self.compare_variant_names(path, &old_variants, &new_variants);
+				for (name, old_discriminant) in &old_variants {
+					let new_discriminant = new_variants
+						.iter()
+						.find(|(new_name, _)| new_name == name)
+						.map(|(_, discriminant)| *discriminant);
+					match new_discriminant {
+						Some(new_discriminant) if new_discriminant != *old_discriminant => self.push(
+							path,
+							ChangeKind::DiscriminantChanged {
+								variant: String::from(*name),
+								old: *old_discriminant,
+								new: new_discriminant,
+							},
+						),
+						_ => (),
+					}
+				}
+			}
+			(TypeDefKind::Enum(old), TypeDefKind::Enum(new)) => {
+				let old_variants = old
+					.indexed_variants()
+					.map(|(index, variant)| (self.old_string(variant.name()), (index, variant)))
+					.collect::<Vec<_>>();
+				let new_variants = new
+					.indexed_variants()
+					.map(|(index, variant)| (self.new_string(variant.name()), (index, variant)))
+					.collect::<Vec<_>>();
+				// Variants are encoded by their indices and thus may be reordered freely.
+				self.compare_variant_names(path, &old_variants, &new_variants);
+				for (name, (old_index, old_variant)) in &old_variants {
+					if let Some((_, (new_index, new_variant))) =
+						new_variants.iter().find(|(new_name, _)| new_name == name)
+					{
+						if old_index != new_index {
+							self.push(
+								path,
+								ChangeKind::IndexChanged {
+									variant: String::from(*name),
+									old: *old_index,
+									new: *new_index,
+								},
+							);
+						}
+						let variant_path = format!("{}::{}", path, name);
+						match (old_variant, new_variant) {
+							(EnumVariant::Unit(_), EnumVariant::Unit(_)) => (),
+							(EnumVariant::Struct(old), EnumVariant::Struct(new)) => {
+								self.compare_named_fields(&variant_path, old.fields(), new.fields())
+							}
+							(EnumVariant::TupleStruct(old), EnumVariant::TupleStruct(new)) => {
+								self.compare_unnamed_fields(&variant_path, old.fields(), new.fields())
+							}
+							_ => self.push(
+								path,
+								ChangeKind::VariantKindChanged {
+									variant: String::from(*name),
+								},
+							),
+						}
+					}
+				}
+			}
+			_ => self.push(path, ChangeKind::DefinitionChanged),
+		}
+	}
+
+	/// Reports added and removed variants.
+	fn compare_variant_names<T, U>(&mut self, path: &str, old: &[(&str, T)], new: &[(&str, U)]) {
+		let old_names = old.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+		let new_names = new.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+		for name in old_names.iter().filter(|name| !new_names.contains(name)) {
+			self.push(
+				path,
+				ChangeKind::VariantRemoved {
+					variant: String::from(*name),
+				},
+			);
+		}
+		for name in new_names.iter().filter(|name| !old_names.contains(name)) {
+			self.push(
+				path,
+				ChangeKind::VariantAdded {
+					variant: String::from(*name),
+				},
+			);
+		}
+	}
+
+	fn compare_named_fields(&mut self, path: &str, old: &[NamedField<CompactForm>], new: &[NamedField<CompactForm>]) {
+		let old_fields = old
+			.iter()
+			.map(|field| (self.old_string(field.name()), self.old_path(*field.ty())))
+			.collect::<Vec<_>>();
+		let new_fields = new
+			.iter()
+			.map(|field| (self.new_string(field.name()), self.new_path(*field.ty())))
+			.collect::<Vec<_>>();
+		for (name, _) in old_fields.iter().filter(|(name, _)| !contains(&new_fields, name)) {
+			self.push(
+				path,
+				ChangeKind::FieldRemoved {
+					field: String::from(*name),
+				},
+			);
+		}
+		for (name, _) in new_fields.iter().filter(|(name, _)| !contains(&old_fields, name)) {
+			self.push(
+				path,
+				ChangeKind::FieldAdded {
+					field: String::from(*name),
+				},
+			);
+		}
+		let old_names = old_fields.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+		let new_names = new_fields.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+		if !same_relative_order(&old_names, &new_names) {
+			self.push(path, ChangeKind::FieldsReordered);
+		}
+		for (name, old_ty) in &old_fields {
+			if let Some((_, new_ty)) = new_fields.iter().find(|(new_name, _)| new_name == name) {
+				self.compare_field_types(path, name, old_ty, new_ty);
+			}
+		}
+	}
+
+	fn compare_unnamed_fields(
+		&mut self,
+		path: &str,
+		old: &[UnnamedField<CompactForm>],
+		new: &[UnnamedField<CompactForm>],
+	) {
+		for (position, old_field) in old.iter().enumerate() {
+			let field = format!("{}", position);
+			match new.get(position) {
+				Some(new_field) => {
+					let old_ty = self.old_path(*old_field.ty());
+					let new_ty = self.new_path(*new_field.ty());
+					self.compare_field_types(path, &field, &old_ty, &new_ty);
+				}
+				None => self.push(path, ChangeKind::FieldRemoved { field }),
+			}
+		}
+		for position in old.len()..new.len() {
+			self.push(
+				path,
+				ChangeKind::FieldAdded {
+					field: format!("{}", position),
+				},
+			);
+		}
+	}
+
+	fn compare_field_types(&mut self, path: &str, field: &str, old: &str, new: &str) {
+		if old != new {
+			self.push(
+				path,
+				ChangeKind::FieldTypeChanged {
+					field: String::from(field),
+					old: String::from(old),
+					new: String::from(new),
+				},
+			);
+		}
+	}
+
+	fn old_string(&self, symbol: &UntrackedSymbol<&'static str>) -> &'a str {
+		self.old
+			.resolve_string(*symbol)
+			.expect("registries only contain valid string symbols")
+	}
+
+	fn new_string(&self, symbol: &UntrackedSymbol<&'static str>) -> &'a str {
+		self.new
+			.resolve_string(*symbol)
+			.expect("registries only contain valid string symbols")
+	}
+
+	fn old_path(&self, symbol: UntrackedSymbol<AnyTypeId>) -> String {
+		self.old
+			.type_path(symbol)
+			.expect("registries only contain valid type symbols")
+	}
+
+	fn new_path(&self, symbol: UntrackedSymbol<AnyTypeId>) -> String {
+		self.new
+			.type_path(symbol)
+			.expect("registries only contain valid type symbols")
+	}
+}
+
+/// Returns `true` if the fields contain a field with the given name.
+fn contains(fields: &[(&str, String)], name: &str) -> bool {
+	fields.iter().any(|(field, _)| *field == name)
+}
+
+/// Returns `true` if the names present in both lists appear in the same order.
+fn same_relative_order(old: &[&str], new: &[&str]) -> bool {
+	let old_order = old.iter().filter(|name| new.contains(name));
+	let new_order = new.iter().filter(|name| old.contains(name));
+	old_order.eq(new_order)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	/// Defines a type in the `app` namespace with the given definition.
+	macro_rules! app_type {
+		($ty:ident, $def:expr) => {
+			pub struct $ty;
+
+			impl HasTypeId for $ty {
+				fn type_id() -> TypeId {
+					TypeIdCustom::new(stringify!($ty), Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+				}
+			}
+
+			impl HasTypeDef for $ty {
+				fn type_def() -> TypeDef {
+					$def.into()
+				}
+			}
+		};
+	}
+
+	mod v1 {
+		use crate::*;
+
+		app_type!(
+			Point,
+			TypeDefStruct::new(vec![
+				NamedField::of::<u32>("x"),
+				NamedField::of::<u32>("y"),
+				NamedField::of::<u8>("z"),
+			])
+		);
+		app_type!(Pair, TypeDefTupleStruct::new(vec![UnnamedField::of::<u8>()]));
+		app_type!(
+			Color,
+			TypeDefClikeEnum::new(vec![
+				ClikeEnumVariant::new("Red", 0u64),
+				ClikeEnumVariant::new("Blue", 1u64)
+			])
+		);
+		app_type!(
+			Shape,
+			TypeDefEnum::new(vec![
+				EnumVariant::Unit(EnumVariantUnit::new("Empty")),
+				EnumVariant::TupleStruct(EnumVariantTupleStruct::new("Circle", vec![UnnamedField::of::<u32>()])),
+				EnumVariant::Unit(EnumVariantUnit::new("Square")),
+			])
+		);
+		app_type!(Legacy, TypeDefTupleStruct::unit());
+	}
+
+	mod v2 {
+		use crate::*;
+
+		app_type!(
+			Point,
+			TypeDefStruct::new(vec![
+				NamedField::of::<u32>("y"),
+				NamedField::of::<u32>("x"),
+				NamedField::of::<u16>("z"),
+				NamedField::of::<bool>("visible"),
+			])
+		);
+		app_type!(
+			Pair,
+			TypeDefTupleStruct::new(vec![UnnamedField::of::<u8>(), UnnamedField::of::<u8>()])
+		);
+		app_type!(
+			Color,
+			TypeDefClikeEnum::new(vec![
+				ClikeEnumVariant::new("Blue", 1u64),
+				ClikeEnumVariant::new("Red", 2u64),
+				ClikeEnumVariant::new("Green", 3u64),
+			])
+		);
+		app_type!(
+			Shape,
+			TypeDefEnum::new(vec![
+				EnumVariant::Unit(EnumVariantUnit::new("Empty")),
+				EnumVariant::Unit(EnumVariantUnit::new("Circle")),
+				EnumVariant::Unit(EnumVariantUnit::new("Square")),
+				EnumVariant::Unit(EnumVariantUnit::new("Triangle")),
+			])
+		);
+		app_type!(Fresh, TypeDefTupleStruct::unit());
+	}
+
+	fn registry_of(types: Vec<MetaType>) -> OwnedRegistry {
+		let mut registry = Registry::new();
+		for ty in &types {
+			registry.register_type(ty);
+		}
+		registry.into()
+	}
+
+	fn change(path: &str, kind: ChangeKind) -> Change {
+		Change {
+			path: path.into(),
+			kind,
+		}
+	}
+
+	#[test]
+	fn identical_registries_are_compatible() {
+		let registry = || registry_of(vec![v1::Point::meta_type(), <Option<v1::Shape>>::meta_type()]);
+		let report = compare(&registry(), &registry());
+		assert_eq!(report.changes(), &[]);
+		assert!(report.is_compatible());
+	}
+
+	#[test]
+	fn changed_fields_are_breaking() {
+		let old = registry_of(vec![v1::Point::meta_type(), v1::Pair::meta_type()]);
+		let new = registry_of(vec![v2::Point::meta_type(), v2::Pair::meta_type()]);
+		let report = compare(&old, &new);
+		assert_eq!(
+			report.changes(),
+			&[
+				change("app::Pair", ChangeKind::FieldAdded { field: "1".into() }),
+				change(
+					"app::Point",
+					ChangeKind::FieldAdded {
+						field: "visible".into()
+					}
+				),
+				change("app::Point", ChangeKind::FieldsReordered),
+				change(
+					"app::Point",
+					ChangeKind::FieldTypeChanged {
+						field: "z".into(),
+						old: "u8".into(),
+						new: "u16".into(),
+					}
+				),
+			]
+		);
+		assert!(!report.is_compatible());
+	}
+
+	#[test]
+	fn changed_variants() {
+		let old = registry_of(vec![v1::Color::meta_type(), v1::Shape::meta_type()]);
+		let new = registry_of(vec![v2::Color::meta_type(), v2::Shape::meta_type()]);
+		let report = compare(&old, &new);
+		assert_eq!(
+			report.changes(),
+			&[
+				change(
+					"app::Color",
+					ChangeKind::VariantAdded {
+						variant: "Green".into()
+					}
+				),
+				change(
+					"app::Color",
+					ChangeKind::DiscriminantChanged {
+						variant: "Red".into(),
+						old: 0,
+						new: 2,
+					}
+				),
+				change(
+					"app::Shape",
+					ChangeKind::VariantAdded {
+						variant: "Triangle".into()
+					}
+				),
+				change(
+					"app::Shape",
+					ChangeKind::VariantKindChanged {
+						variant: "Circle".into()
+					}
+				),
+			]
+		);
+		let breaking = report.breaking_changes().cloned().collect::<Vec<_>>();
+		assert_eq!(breaking, vec![report.changes()[1].clone(), report.changes()[3].clone()]);
+	}
+
+	#[test]
+	fn changed_reprs_are_breaking() {
+		mod v3 {
+			use crate::*;
+
+			app_type!(
+				Color,
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Red", 0u64),
+					ClikeEnumVariant::new("Blue", 1u64)
+				])
+				.with_repr::<u8>()
+			);
+		}
+
+		mod v4 {
+			use crate::*;
+
+			app_type!(
+				Color,
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Red", 0u64),
+					ClikeEnumVariant::new("Blue", 1u64)
+				])
+				.with_repr::<u16>()
+			);
+		}
+
+		let old = registry_of(vec![v1::Color::meta_type()]);
+		// Without a repr discriminants are encoded as `u8`.
+		assert!(compare(&old, &registry_of(vec![v3::Color::meta_type()]))
+			.changes()
+			.is_empty());
+		let report = compare(&old, &registry_of(vec![v4::Color::meta_type()]));
+		assert_eq!(
+			report.changes(),
+			&[change(
+				"app::Color",
+				ChangeKind::ReprChanged {
+					old: None,
+					new: Some(TypeIdPrimitive::U16),
+				}
+			)]
+		);
+		assert!(!report.is_compatible());
+	}
+
+	#[test]
+	fn added_and_removed_types() {
+		let old = registry_of(vec![v1::Legacy::meta_type(), v1::Pair::meta_type()]);
+		let new = registry_of(vec![v2::Fresh::meta_type(), v1::Pair::meta_type()]);
+		let report = compare(&old, &new);
+		assert_eq!(
+			report.changes(),
+			&[
+				change("app::Fresh", ChangeKind::TypeAdded),
+				change("app::Legacy", ChangeKind::TypeRemoved),
+			]
+		);
+		assert_eq!(report.changes()[0].compatibility(), Compatibility::Compatible);
+		assert_eq!(report.changes()[1].compatibility(), Compatibility::Breaking);
+	}
+
+	#[test]
+	fn reindexed_enum_variants_are_breaking() {
+		mod v3 {
+			use crate::*;
+
+			app_type!(
+				Shape,
+				TypeDefEnum::new(vec![
+					EnumVariant::Unit(EnumVariantUnit::new("Square")),
+					EnumVariant::Unit(EnumVariantUnit::new("Point")),
+					EnumVariant::Unit(EnumVariantUnit::new("Empty")),
+				])
+			);
+		}
+
+		let old = registry_of(vec![v2::Shape::meta_type()]);
+		let new = registry_of(vec![v3::Shape::meta_type()]);
+		let kinds = compare(&old, &new)
+			.changes()
+			.iter()
+			.map(|change| change.kind.clone())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			kinds,
+			vec![
+				ChangeKind::VariantRemoved {
+					variant: "Circle".into()
+				},
+				ChangeKind::VariantRemoved {
+					variant: "Triangle".into()
+				},
+				ChangeKind::VariantAdded {
+					variant: "Point".into()
+				},
+				ChangeKind::IndexChanged {
+					variant: "Empty".into(),
+					old: 0,
+					new: 2,
+				},
+				ChangeKind::IndexChanged {
+					variant: "Square".into(),
+					old: 2,
+					new: 0,
+				},
+			]
+		);
+	}
+}
@@ -0,0 +1,742 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Type directed decoding of SCALE encoded bytes into dynamic values.
+//!
+//! The layout of the bytes is derived from the type identifiers and definitions
+//! of a registry:
+//!
+//! - Primitives are encoded in little endian, booleans as a single byte,
+//!   characters as their `u32` code point and strings as compact length prefixed UTF-8.
+//! - Arrays and tuples are the concatenation of their elements.
+//! - Slices are compact length prefixed sequences of their elements.
+//! - Structs and tuple structs are the concatenation of their fields.
+//! - Enums are the `u8` index of their variant followed by the variant's fields.
+//!   The index is recorded with the variant and defaults to the variant's position.
+//! - C-like enums are the discriminant of their variant at the width of their `#[repr(..)]`,
+//!   or as a single `u8` if there is none.
+//! - `NonZero*` integers of the prelude are tuple structs of their integer.
+//!   Fields marked as non-zero, such as theirs, are rejected if they are zero.
+//!
+//! Since the input may be untrusted, sequence lengths are checked against the remaining input
+//! before more than one element is decoded and the nesting of values is limited to [`MAX_DEPTH`].
+//! Values encoded without any bytes, such as `()`, share a budget of [`MAX_ZERO_SIZED_LEN`]
+//! across the whole input, so that nesting them does not multiply the number of decoded values.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm,
+	interner::UntrackedSymbol,
+	value::{Composite, Primitive, Value, Variant},
+	EnumVariant, NamedField, OwnedRegistry, TypeDefKind, TypeId, TypeIdPrimitive, UnnamedField,
+};
+
+/// An error that may be encountered upon decoding bytes into a value.
+///
+/// Offsets are byte positions within the decoded input.
+#[derive(PartialEq, Eq, Debug)]
+pub enum DecodeError {
+	/// If the input ended before the value has been decoded completely.
+	UnexpectedEof {
+		/// The offset at which more bytes were required.
+		offset: usize,
+	},
+	/// If a byte does not encode a boolean.
+	InvalidBool {
+		/// The offset of the erroneous byte.
+		offset: usize,
+		/// The erroneous byte.
+		byte: u8,
+	},
+	/// If a code point does not encode a character.
+	InvalidChar {
+		/// The offset of the erroneous code point.
+		offset: usize,
+		/// The erroneous code point.
+		code_point: u32,
+	},
+	/// If the bytes of a string are not valid UTF-8.
+	InvalidUtf8 {
+		/// The offset of the string bytes.
+		offset: usize,
+	},
+	/// If a compact encoded length does not fit into a `usize`.
+	InvalidCompact {
+		/// The offset of the compact encoded length.
+		offset: usize,
+	},
+	/// If an enum variant index matches no variant.
+	InvalidVariant {
+		/// The offset of the variant index.
+		offset: usize,
+		/// The enum type.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The erroneous variant index.
+		index: u8,
+	},
+	/// If a C-like enum discriminant matches no variant.
+	InvalidDiscriminant {
+		/// The offset of the discriminant.
+		offset: usize,
+		/// The C-like enum type.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The erroneous discriminant as evaluated by `Variant as i128`.
+		discriminant: i128,
+	},
+	/// If a type symbol does not belong to the registry.
+	UnknownType {
+		/// The unknown type symbol.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If a string symbol does not belong to the registry.
+	UnknownString {
+		/// The unknown string symbol.
+		string: UntrackedSymbol<&'static str>,
+	},
+	/// If the layout of a type cannot be derived from its definition.
+	///
+	/// This is the case for unions, generic parameters and custom types with builtin definitions.
+	UnsupportedType {
+		/// The unsupported type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If a value of a type without values such as the never type is to be decoded.
+	Uninhabited {
+		/// The offset of the value.
+		offset: usize,
+		/// The uninhabited type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If the value of a non-zero field is zero, e.g. of a `NonZero*` integer.
+	InvalidNonZero {
+		/// The offset of the field.
+		offset: usize,
+		/// The type of which the field is zero.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If a sequence is longer than the remaining input allows.
+	///
+	/// Sequences of zero-sized elements may not be longer than the remaining
+	/// budget of [`MAX_ZERO_SIZED_LEN`] allows.
+	InvalidLength {
+		/// The offset of the compact encoded length.
+		offset: usize,
+		/// The erroneous length.
+		len: usize,
+	},
+	/// If more than [`MAX_ZERO_SIZED_LEN`] values are encoded without any bytes.
+	ZeroSizedLimitExceeded {
+		/// The offset of the value exceeding the limit.
+		offset: usize,
+	},
+	/// If values are nested deeper than [`MAX_DEPTH`].
+	DepthLimitExceeded {
+		/// The offset of the value exceeding the limit.
+		offset: usize,
+	},
+	/// If bytes remain after the value has been decoded completely.
+	TrailingBytes {
+		/// The offset of the first remaining byte.
+		offset: usize,
+	},
+}
+
+/// The maximum nesting depth of decoded values.
+pub const MAX_DEPTH: usize = 256;
+
+/// The maximum number of values encoded without any bytes, such as the elements of `Vec<()>`,
+/// that are decoded from a single input.
+///
+/// The budget is shared by all values of the input, nested ones included.
+pub const MAX_ZERO_SIZED_LEN: usize = 1 << 16;
+
+/// Decodes the input into a value of the given type.
+///
+/// Returns an error if the input is malformed or not consumed completely.
+pub fn decode(registry: &OwnedRegistry, ty: UntrackedSymbol<AnyTypeId>, input: &[u8]) -> Result<Value, DecodeError> {
+	let (value, consumed) = decode_prefix(registry, ty, input)?;
+	if consumed != input.len() {
+		return Err(DecodeError::TrailingBytes { offset: consumed });
+	}
+	Ok(value)
+}
+
+/// Decodes a value of the given type from the start of the input.
+///
+/// Returns the decoded value and the number of consumed bytes.
+pub fn decode_prefix(
+	registry: &OwnedRegistry,
+	ty: UntrackedSymbol<AnyTypeId>,
+	input: &[u8],
+) -> Result<(Value, usize), DecodeError> {
+	let mut decoder = Decoder {
+		registry,
+		input,
+		offset: 0,
+		depth: 0,
+		zero_sized_budget: MAX_ZERO_SIZED_LEN,
+	};
+	let value = decoder.decode_type(ty)?;
+	Ok((value, decoder.offset))
+}
+
+/// Decodes values while keeping track of the current input offset.
+struct Decoder<'a> {
+	registry: &'a OwnedRegistry,
+	input: &'a [u8],
+	offset: usize,
+	depth: usize,
+	/// The number of values encoded without any bytes that may still be decoded.
+	zero_sized_budget: usize,
+}
+
+impl<'a> Decoder<'a> {
+	fn decode_type(&mut self, ty: UntrackedSymbol<AnyTypeId>) -> Result<Value, DecodeError> {
+		if self.depth == MAX_DEPTH {
+			return Err(DecodeError::DepthLimitExceeded { offset: self.offset });
+		}
+		let start = self.offset;
+		self.depth += 1;
+		let value = self.decode_nested(ty);
+		self.depth -= 1;
+		let value = value?;
+		if self.offset == start {
+			if self.zero_sized_budget == 0 {
+				return Err(DecodeError::ZeroSizedLimitExceeded { offset: start });
+			}
+			self.zero_sized_budget -= 1;
+		}
+		Ok(value)
+	}
+
+	fn decode_nested(&mut self, ty: UntrackedSymbol<AnyTypeId>) -> Result<Value, DecodeError> {
+		let type_id_def = self.registry.resolve(ty).ok_or(DecodeError::UnknownType { ty })?;
+		match type_id_def.id() {
+			TypeId::Primitive(primitive) => self.decode_primitive(ty, primitive).map(Value::Primitive),
+			TypeId::Array(array) => {
+				let elems = (0..array.len)
+					.map(|_| self.decode_type(array.type_param))
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(Value::Array(elems))
+			}
+			TypeId::Slice(slice) => self.decode_sequence(*slice.type_param()).map(Value::Sequence),
+			TypeId::Tuple(tuple) => {
+				let elems = tuple
+					.type_params
+					.iter()
+					.map(|param| self.decode_type(*param))
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok(Value::Tuple(elems))
+			}
+			TypeId::Custom(_) => self.decode_custom(ty, type_id_def.def().kind()),
+			TypeId::Parameter(_) => Err(DecodeError::UnsupportedType { ty }),
+		}
+	}
+
+	fn decode_custom(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		kind: &TypeDefKind<CompactForm>,
+	) -> Result<Value, DecodeError> {
+		match kind {
+			TypeDefKind::Struct(r#struct) => self.decode_named_fields(r#struct.fields()).map(Value::Composite),
+			TypeDefKind::TupleStruct(tuple_struct) => self
+				.decode_unnamed_fields(ty, tuple_struct.fields())
+				.map(Value::Composite),
+			TypeDefKind::Enum(r#enum) => {
+				let offset = self.offset;
+				let index = self.take_byte()?;
+				let (_, variant) = r#enum
+					.indexed_variants()
+					.find(|(variant_index, _)| *variant_index == usize::from(index))
+					.ok_or(DecodeError::InvalidVariant { offset, ty, index })?;
+				let fields = match variant {
+					EnumVariant::Unit(_) => Composite::Unnamed(vec![]),
+					EnumVariant::Struct(r#struct) => self.decode_named_fields(r#struct.fields())?,
+					EnumVariant::TupleStruct(tuple_struct) => self.decode_unnamed_fields(ty, tuple_struct.fields())?,
+				};
+				Ok(Value::Variant(Variant {
+					name: self.resolve_string(*variant.name())?,
+					fields,
+				}))
+			}
+			TypeDefKind::ClikeEnum(clike_enum) => {
+				let offset = self.offset;
+				let (width, _) = clike_enum.discriminant_layout();
+				let discriminant = clike_enum.discriminant_from_le_bytes(self.take(width)?);
+				let variant = clike_enum
+					.variants()
+					.iter()
+					.find(|variant| variant.discriminant() == discriminant)
+					.ok_or(DecodeError::InvalidDiscriminant {
+						offset,
+						ty,
+						discriminant,
+					})?;
+				Ok(Value::Variant(Variant {
+					name: self.resolve_string(*variant.name())?,
+					fields: Composite::Unnamed(vec![]),
+				}))
+			}
+			TypeDefKind::Union(_) | TypeDefKind::Builtin => Err(DecodeError::UnsupportedType { ty }),
+		}
+	}
+
+	fn decode_sequence(&mut self, elem: UntrackedSymbol<AnyTypeId>) -> Result<Vec<Value>, DecodeError> {
+		let offset = self.offset;
+		let len = self.decode_compact_len()?;
+		if len == 0 {
+			return Ok(Vec::new());
+		}
+		// A type is either always encoded without any bytes or with at least one byte per value,
+		// so the first element tells whether the length is bounded by the remaining input
+		// or by the remaining budget of zero-sized values.
+		let start = self.offset;
+		let budget = self.zero_sized_budget;
+		let first = self.decode_type(elem)?;
+		let remaining = self.input.len() - self.offset;
+		let (max_len, capacity) = match remaining.checked_div(self.offset - start) {
+			// Elements may differ in size, so only reserve what the first one allows for.
+			Some(by_first) => (1 + remaining, len.min(1 + by_first)),
+			// Every element is charged the same share of the budget as the first one.
+			None => {
+				let max_len = 1 + self.zero_sized_budget / (budget - self.zero_sized_budget);
+				(max_len, len)
+			}
+		};
+		if len > max_len {
+			return Err(DecodeError::InvalidLength { offset, len });
+		}
+		let mut elems = Vec::with_capacity(capacity);
+		elems.push(first);
+		for _ in 1..len {
+			elems.push(self.decode_type(elem)?);
+		}
+		Ok(elems)
+	}
+
+	fn decode_named_fields(&mut self, fields: &[NamedField<CompactForm>]) -> Result<Composite, DecodeError> {
+		let fields = fields
+			.iter()
+			.map(|field| Ok((self.resolve_string(*field.name())?, self.decode_type(*field.ty())?)))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Composite::Named(fields))
+	}
+
+	fn decode_unnamed_fields(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		fields: &[UnnamedField<CompactForm>],
+	) -> Result<Composite, DecodeError> {
+		let fields = fields
+			.iter()
+			.map(|field| {
+				let offset = self.offset;
+				let value = self.decode_type(*field.ty())?;
+				match value {
+					Value::Primitive(primitive) if field.is_non_zero() && primitive.is_zero() => {
+						Err(DecodeError::InvalidNonZero { offset, ty })
+					}
+					value => Ok(value),
+				}
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Composite::Unnamed(fields))
+	}
+
+	fn decode_primitive(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		primitive: &TypeIdPrimitive,
+	) -> Result<Primitive, DecodeError> {
+		let primitive = match primitive {
+			TypeIdPrimitive::Bool => {
+				let offset = self.offset;
+				match self.take_byte()? {
+					0 => Primitive::Bool(false),
+					1 => Primitive::Bool(true),
+					byte => return Err(DecodeError::InvalidBool { offset, byte }),
+				}
+			}
+			TypeIdPrimitive::Char => {
+				let offset = self.offset;
+				let code_point = u32::from_le_bytes(self.take_array()?);
+				let ch = char::from_u32(code_point).ok_or(DecodeError::InvalidChar { offset, code_point })?;
+				Primitive::Char(ch)
+			}
+			TypeIdPrimitive::Str => {
+				let len = self.decode_compact_len()?;
+				let offset = self.offset;
+				let bytes = self.take(len)?;
+				let string = String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8 { offset })?;
+				Primitive::Str(string)
+			}
+			TypeIdPrimitive::U8 => Primitive::U8(self.take_byte()?),
+			TypeIdPrimitive::U16 => Primitive::U16(u16::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::U32 => Primitive::U32(u32::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::U64 => Primitive::U64(u64::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::U128 => Primitive::U128(u128::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::I8 => Primitive::I8(i8::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::I16 => Primitive::I16(i16::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::I32 => Primitive::I32(i32::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::I64 => Primitive::I64(i64::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::I128 => Primitive::I128(i128::from_le_bytes(self.take_array()?)),
+			TypeIdPrimitive::F32 => Primitive::F32(self.take_array()?),
+			TypeIdPrimitive::F64 => Primitive::F64(self.take_array()?),
+			TypeIdPrimitive::Unit => Primitive::Unit,
+			TypeIdPrimitive::Never => {
+				return Err(DecodeError::Uninhabited {
+					offset: self.offset,
+					ty,
+				})
+			}
+		};
+		Ok(primitive)
+	}
+
+	/// Decodes a SCALE compact encoded length.
+	fn decode_compact_len(&mut self) -> Result<usize, DecodeError> {
+		let offset = self.offset;
+		let first = self.take_byte()?;
+		let len = match first & 0b11 {
+			0b00 => u64::from(first >> 2),
+			0b01 => u64::from(u16::from_le_bytes([first, self.take_byte()?]) >> 2),
+			0b10 => {
+				let rest = self.take(3)?;
+				u64::from(u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2)
+			}
+			_ => {
+				let num_bytes = (first >> 2) as usize + 4;
+				if num_bytes > 8 {
+					return Err(DecodeError::InvalidCompact { offset });
+				}
+				let mut bytes = [0u8; 8];
+				bytes[..num_bytes].copy_from_slice(self.take(num_bytes)?);
+				u64::from_le_bytes(bytes)
+			}
+		};
+		if len > usize::MAX as u64 {
+			return Err(DecodeError::InvalidCompact { offset });
+		}
+		Ok(len as usize)
+	}
+
+	fn resolve_string(&self, string: UntrackedSymbol<&'static str>) -> Result<String, DecodeError> {
+		self.registry
+			.resolve_string(string)
+			.map(String::from)
+			.ok_or(DecodeError::UnknownString { string })
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+		let remaining = &self.input[self.offset..];
+		if remaining.len() < len {
+			return Err(DecodeError::UnexpectedEof {
+				offset: self.input.len(),
+			});
+		}
+		self.offset += len;
+		Ok(&remaining[..len])
+	}
+
+	fn take_byte(&mut self) -> Result<u8, DecodeError> {
+		self.take(1).map(|bytes| bytes[0])
+	}
+
+	fn take_array<A>(&mut self) -> Result<A, DecodeError>
+	where
+		A: Default + AsMut<[u8]>,
+	{
+		let mut array = A::default();
+		let len = array.as_mut().len();
+		array.as_mut().copy_from_slice(self.take(len)?);
+		Ok(array)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		tests::registry_with, HasTypeDef, HasTypeId, Metadata, Namespace, TypeDef, TypeDefStruct, TypeIdCustom,
+	};
+
+	fn assert_decode<T>(input: &[u8], expected: Value)
+	where
+		T: Metadata + ?Sized + 'static,
+	{
+		let (registry, symbol) = registry_with::<T>();
+		assert_eq!(decode(&registry, symbol, input), Ok(expected));
+	}
+
+	fn assert_decode_err<T>(input: &[u8], expected: DecodeError)
+	where
+		T: Metadata + ?Sized + 'static,
+	{
+		let (registry, symbol) = registry_with::<T>();
+		assert_eq!(decode(&registry, symbol, input), Err(expected));
+	}
+
+	fn str_value(s: &str) -> Value {
+		Primitive::Str(s.into()).into()
+	}
+
+	#[test]
+	fn primitives() {
+		assert_decode::<bool>(&[1], Primitive::Bool(true).into());
+		assert_decode::<char>(&[0x41, 0, 0, 0], Primitive::Char('A').into());
+		assert_decode::<u16>(&[0x34, 0x12], Primitive::U16(0x1234).into());
+		assert_decode::<i32>(&[0xff, 0xff, 0xff, 0xff], Primitive::I32(-1).into());
+		assert_decode::<u128>(&[1; 16], Primitive::U128(u128::from_le_bytes([1; 16])).into());
+		assert_decode::<String>(&[3 << 2, b'a', b'b', b'c'], str_value("abc"));
+	}
+
+	#[test]
+	fn compact_lengths() {
+		let (registry, symbol) = registry_with::<[u8]>();
+		let decode_len = |input: &[u8]| match decode_prefix(&registry, symbol, input) {
+			Ok((Value::Sequence(elems), _)) => Ok(elems.len()),
+			Ok(other) => panic!("expected sequence but found {:?}", other),
+			Err(err) => Err(err),
+		};
+		// single byte mode
+		assert_eq!(decode_len(&[0]), Ok(0));
+		// two byte mode
+		let mut input = vec![0b0000_0001, 0b0000_0001];
+		input.extend(vec![7; 64]);
+		assert_eq!(decode_len(&input), Ok(64));
+		// four byte mode
+		let mut input = vec![0b0000_0010, 0, 1, 0];
+		input.extend(vec![7; 1 << 14]);
+		assert_eq!(decode_len(&input), Ok(1 << 14));
+		// big integer mode with more bytes than supported
+		assert_eq!(
+			decode_len(&[0b1111_1111]),
+			Err(DecodeError::InvalidCompact { offset: 0 })
+		);
+	}
+
+	#[test]
+	fn sequences_arrays_and_tuples() {
+		assert_decode::<Vec<u8>>(
+			&[2 << 2, 1, 2],
+			Value::Composite(Composite::Named(vec![(
+				"elems".into(),
+				Value::Sequence(vec![Primitive::U8(1).into(), Primitive::U8(2).into()]),
+			)])),
+		);
+		assert_decode::<[bool; 2]>(
+			&[0, 1],
+			Value::Array(vec![Primitive::Bool(false).into(), Primitive::Bool(true).into()]),
+		);
+		assert_decode::<(u8, String)>(
+			&[7, 1 << 2, b'x'],
+			Value::Tuple(vec![Primitive::U8(7).into(), str_value("x")]),
+		);
+		assert_decode::<()>(&[], Primitive::Unit.into());
+	}
+
+	#[test]
+	fn variants() {
+		assert_decode::<Option<u8>>(
+			&[0],
+			Variant {
+				name: "None".into(),
+				fields: Composite::Unnamed(vec![]),
+			}
+			.into(),
+		);
+		assert_decode::<Result<u8, bool>>(
+			&[1, 1],
+			Variant {
+				name: "Err".into(),
+				fields: Composite::Unnamed(vec![Primitive::Bool(true).into()]),
+			}
+			.into(),
+		);
+	}
+
+	#[test]
+	fn errors() {
+		assert_decode_err::<u32>(&[1, 2, 3], DecodeError::UnexpectedEof { offset: 3 });
+		assert_decode_err::<u8>(&[1, 2], DecodeError::TrailingBytes { offset: 1 });
+		assert_decode_err::<(u8, bool)>(&[0, 2], DecodeError::InvalidBool { offset: 1, byte: 2 });
+		assert_decode_err::<char>(
+			&[0x00, 0xd8, 0, 0],
+			DecodeError::InvalidChar {
+				offset: 0,
+				code_point: 0xd800,
+			},
+		);
+		assert_decode_err::<String>(&[1 << 2, 0xff], DecodeError::InvalidUtf8 { offset: 1 });
+
+		let (registry, symbol) = registry_with::<Option<u8>>();
+		assert_eq!(
+			decode(&registry, symbol, &[2]),
+			Err(DecodeError::InvalidVariant {
+				offset: 0,
+				ty: symbol,
+				index: 2
+			})
+		);
+		let unknown = UntrackedSymbol::from_index(registry.types().len());
+		assert_eq!(
+			decode(&registry, unknown, &[]),
+			Err(DecodeError::UnknownType { ty: unknown })
+		);
+
+		let (registry, symbol) = registry_with::<(u8, Infallible)>();
+		let never = match registry.resolve(symbol).unwrap().id() {
+			TypeId::Tuple(tuple) => tuple.type_params[1],
+			_ => unreachable!("tuples are registered as tuples"),
+		};
+		assert_eq!(
+			decode(&registry, symbol, &[0]),
+			Err(DecodeError::Uninhabited { offset: 1, ty: never })
+		);
+	}
+
+	#[test]
+	fn sequence_lengths_are_bounded() {
+		assert_decode_err::<[u8]>(&[3 << 2, 1, 2], DecodeError::InvalidLength { offset: 0, len: 3 });
+		assert_decode_err::<[u16]>(&[2 << 2, 1, 0, 2], DecodeError::UnexpectedEof { offset: 4 });
+		// A four byte compact length larger than the input must not be allocated upfront.
+		assert_decode_err::<[u64]>(&[0xfe, 0xff, 0xff, 0xff, 0], DecodeError::UnexpectedEof { offset: 5 });
+		assert_decode_err::<[u8]>(
+			&[0xfe, 0xff, 0xff, 0xff, 0, 0],
+			DecodeError::InvalidLength {
+				offset: 0,
+				len: 0x3fff_ffff,
+			},
+		);
+
+		assert_decode::<[()]>(&[2 << 2], Value::Sequence(vec![Primitive::Unit.into(); 2]));
+		let (registry, symbol) = registry_with::<[()]>();
+		let compact_len = |len: usize| ((len as u32) << 2 | 0b10).to_le_bytes();
+		match decode(&registry, symbol, &compact_len(MAX_ZERO_SIZED_LEN)) {
+			Ok(Value::Sequence(elems)) => assert_eq!(elems.len(), MAX_ZERO_SIZED_LEN),
+			other => panic!("expected sequence but found {:?}", other),
+		}
+		assert_eq!(
+			decode(&registry, symbol, &compact_len(MAX_ZERO_SIZED_LEN + 1)),
+			Err(DecodeError::InvalidLength {
+				offset: 0,
+				len: MAX_ZERO_SIZED_LEN + 1
+			})
+		);
+	}
+
+	#[test]
+	fn zero_sized_values_share_one_budget() {
+		let compact_len = |len: usize| ((len as u32) << 2 | 0b10).to_le_bytes();
+
+		// Each inner sequence is within the limit on its own but not both together.
+		let mut input = vec![2 << 2];
+		input.extend_from_slice(&compact_len(MAX_ZERO_SIZED_LEN));
+		input.extend_from_slice(&compact_len(1));
+		assert_decode_err::<Vec<Vec<()>>>(&input, DecodeError::ZeroSizedLimitExceeded { offset: 9 });
+
+		// Every element of the sequence is charged for itself and its four units.
+		let len = MAX_ZERO_SIZED_LEN / 4;
+		assert_decode_err::<[[(); 4]]>(&compact_len(len), DecodeError::InvalidLength { offset: 0, len });
+		let len = MAX_ZERO_SIZED_LEN / 5;
+		assert_decode::<[[(); 4]]>(
+			&compact_len(len),
+			Value::Sequence(vec![Value::Array(vec![Primitive::Unit.into(); 4]); len]),
+		);
+	}
+
+	#[test]
+	fn discriminants_are_decoded_at_the_width_of_the_repr() {
+		use crate::{ClikeEnumVariant, TypeDefClikeEnum};
+
+		#[allow(unused)]
+		#[repr(u16)]
+		enum Port {
+			Http = 80,
+			Https = 443,
+		}
+
+		impl HasTypeId for Port {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Port", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Port {
+			fn type_def() -> TypeDef {
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Http", Port::Http as i128),
+					ClikeEnumVariant::new("Https", Port::Https as i128),
+				])
+				.with_repr::<u16>()
+				.into()
+			}
+		}
+
+		assert_decode::<Port>(
+			&[0xbb, 0x01],
+			Variant {
+				name: "Https".into(),
+				fields: Composite::Unnamed(vec![]),
+			}
+			.into(),
+		);
+		let (registry, symbol) = registry_with::<Port>();
+		assert_eq!(
+			decode(&registry, symbol, &[80]),
+			Err(DecodeError::UnexpectedEof { offset: 1 })
+		);
+		assert_eq!(
+			decode(&registry, symbol, &[81, 0]),
+			Err(DecodeError::InvalidDiscriminant {
+				offset: 0,
+				ty: symbol,
+				discriminant: 81
+			})
+		);
+	}
+
+	#[test]
+	fn nesting_depth_is_limited() {
+		#[allow(unused)]
+		struct List {
+			next: Option<Box<List>>,
+		}
+
+		impl HasTypeId for List {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("List", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for List {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![NamedField::of::<Option<Box<List>>>("next")]).into()
+			}
+		}
+
+		// Every list node nests an option which nests the next node.
+		let (registry, symbol) = registry_with::<List>();
+		let mut input = vec![1; MAX_DEPTH / 2 - 1];
+		input.push(0);
+		assert!(decode(&registry, symbol, &input).is_ok());
+		assert_eq!(
+			decode(&registry, symbol, &[1; MAX_DEPTH]),
+			Err(DecodeError::DepthLimitExceeded { offset: MAX_DEPTH / 2 })
+		);
+	}
+}
@@ -0,0 +1,659 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Type directed encoding of dynamic values into SCALE encoded bytes.
+//!
+//! This is the inverse of the `decode` module and follows the same layout.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm,
+	interner::UntrackedSymbol,
+	value::{Composite, Primitive, Value, Variant},
+	EnumVariant, NamedField, OwnedRegistry, TypeDefKind, TypeId, TypeIdPrimitive, UnnamedField,
+};
+
+/// An error that may be encountered upon encoding a value.
+///
+/// Reports the type whose definition the value does not match.
+#[derive(PartialEq, Eq, Debug)]
+pub enum EncodeError {
+	/// If the kind of the value does not match the type.
+	///
+	/// For example if a tuple is encoded as a primitive.
+	MismatchedValue {
+		/// The type the value was encoded as.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If the number of elements or fields of the value does not match the type.
+	MismatchedLength {
+		/// The type the value was encoded as.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The number of elements or fields required by the type.
+		expected: usize,
+		/// The number of elements or fields of the value.
+		actual: usize,
+	},
+	/// If the value of a non-zero field is zero, e.g. of a `NonZero*` integer.
+	InvalidNonZero {
+		/// The type of which the field is zero.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If a named field required by the type is missing from the value.
+	MissingField {
+		/// The type the value was encoded as.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The name of the missing field.
+		name: String,
+	},
+	/// If a variant of the value does not exist for the enum type.
+	UnknownVariant {
+		/// The enum type the value was encoded as.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The name of the unknown variant.
+		name: String,
+	},
+	/// If the index of a variant does not fit into a single byte or the discriminant of a
+	/// variant does not fit into the repr of its C-like enum, e.g. if it is negative.
+	VariantOutOfRange {
+		/// The enum type the value was encoded as.
+		ty: UntrackedSymbol<AnyTypeId>,
+		/// The name of the variant.
+		name: String,
+	},
+	/// If a type symbol does not belong to the registry.
+	UnknownType {
+		/// The unknown type symbol.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If a string symbol does not belong to the registry.
+	UnknownString {
+		/// The unknown string symbol.
+		string: UntrackedSymbol<&'static str>,
+	},
+	/// If the layout of a type cannot be derived from its definition.
+	///
+	/// This is the case for unions, generic parameters and custom types with builtin definitions.
+	UnsupportedType {
+		/// The unsupported type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+}
+
+/// Encodes the value as the given type.
+///
+/// Returns an error if the shape of the value does not match the type.
+pub fn encode(registry: &OwnedRegistry, ty: UntrackedSymbol<AnyTypeId>, value: &Value) -> Result<Vec<u8>, EncodeError> {
+	let mut output = Vec::new();
+	encode_to(registry, ty, value, &mut output)?;
+	Ok(output)
+}
+
+/// Encodes the value as the given type and appends the bytes to the output.
+///
+/// The output is left unchanged if an error is returned.
+pub fn encode_to(
+	registry: &OwnedRegistry,
+	ty: UntrackedSymbol<AnyTypeId>,
+	value: &Value,
+	output: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
+	let len = output.len();
+	let result = Encoder { registry, output }.encode_type(ty, value);
+	if result.is_err() {
+		output.truncate(len);
+	}
+	result
+}
+
+/// Encodes values into the output.
+struct Encoder<'a> {
+	registry: &'a OwnedRegistry,
+	output: &'a mut Vec<u8>,
+}
+
+impl Encoder<'_> {
+	fn encode_type(&mut self, ty: UntrackedSymbol<AnyTypeId>, value: &Value) -> Result<(), EncodeError> {
+		let type_id_def = self.registry.resolve(ty).ok_or(EncodeError::UnknownType { ty })?;
+		match (type_id_def.id(), value) {
+			(TypeId::Primitive(primitive), Value::Primitive(value)) => self.encode_primitive(ty, primitive, value),
+			(TypeId::Array(array), Value::Array(elems)) => {
+				expect_len(ty, array.len as usize, elems.len())?;
+				elems
+					.iter()
+					.try_for_each(|elem| self.encode_type(array.type_param, elem))
+			}
+			(TypeId::Slice(slice), Value::Sequence(elems)) => {
+				self.encode_compact_len(elems.len());
+				elems
+					.iter()
+					.try_for_each(|elem| self.encode_type(*slice.type_param(), elem))
+			}
+			(TypeId::Tuple(tuple), Value::Tuple(elems)) => {
+				expect_len(ty, tuple.type_params.len(), elems.len())?;
+				tuple
+					.type_params
+					.iter()
+					.zip(elems)
+					.try_for_each(|(param, elem)| self.encode_type(*param, elem))
+			}
+			(TypeId::Custom(_), value) => self.encode_custom(ty, type_id_def.def().kind(), value),
+			(TypeId::Parameter(_), _) => Err(EncodeError::UnsupportedType { ty }),
+			_ => Err(EncodeError::MismatchedValue { ty }),
+		}
+	}
+
+	fn encode_custom(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		kind: &TypeDefKind<CompactForm>,
+		value: &Value,
+	) -> Result<(), EncodeError> {
+		match (kind, value) {
+			(TypeDefKind::Struct(r#struct), Value::Composite(composite)) => {
+				self.encode_named_fields(ty, r#struct.fields(), composite)
+			}
+			(TypeDefKind::TupleStruct(tuple_struct), Value::Composite(composite)) => {
+				self.encode_unnamed_fields(ty, tuple_struct.fields(), composite)
+			}
+			(TypeDefKind::Enum(r#enum), Value::Variant(Variant { name, fields })) => {
+				let position = self.find_variant(ty, r#enum.variants().iter().map(EnumVariant::name), name)?;
+				let (index, variant) = r#enum
+					.indexed_variants()
+					.nth(position)
+					.expect("found variants are variants of the enum");
+				self.output.push(u8_variant(ty, index as i128, name)?);
+				match variant {
+					EnumVariant::Unit(_) => expect_unit(ty, fields),
+					EnumVariant::Struct(r#struct) => self.encode_named_fields(ty, r#struct.fields(), fields),
+					EnumVariant::TupleStruct(tuple_struct) => {
+						self.encode_unnamed_fields(ty, tuple_struct.fields(), fields)
+					}
+				}
+			}
+			(TypeDefKind::ClikeEnum(clike_enum), Value::Variant(Variant { name, fields })) => {
+				let variants = clike_enum.variants();
+				let index = self.find_variant(ty, variants.iter().map(|variant| variant.name()), name)?;
+				expect_unit(ty, fields)?;
+				let (width, _) = clike_enum.discriminant_layout();
+				let discriminant = variants[index].discriminant();
+				let bytes = &discriminant.to_le_bytes()[..width];
+				if clike_enum.discriminant_from_le_bytes(bytes) != discriminant {
+					return Err(EncodeError::VariantOutOfRange { ty, name: name.into() });
+				}
+				self.output.extend_from_slice(bytes);
+				Ok(())
+			}
+			(TypeDefKind::Union(_), _) | (TypeDefKind::Builtin, _) => Err(EncodeError::UnsupportedType { ty }),
+			_ => Err(EncodeError::MismatchedValue { ty }),
+		}
+	}
+
+	/// Returns the position of the variant with the given name.
+	fn find_variant<'b, I>(&self, ty: UntrackedSymbol<AnyTypeId>, names: I, name: &str) -> Result<usize, EncodeError>
+	where
+		I: IntoIterator<Item = &'b UntrackedSymbol<&'static str>>,
+	{
+		for (index, variant_name) in names.into_iter().enumerate() {
+			if self.resolve_string(*variant_name)? == name {
+				return Ok(index);
+			}
+		}
+		Err(EncodeError::UnknownVariant { ty, name: name.into() })
+	}
+
+	fn encode_named_fields(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		fields: &[NamedField<CompactForm>],
+		composite: &Composite,
+	) -> Result<(), EncodeError> {
+		let values = match composite {
+			Composite::Named(values) => values,
+			Composite::Unnamed(values) if fields.is_empty() && values.is_empty() => return Ok(()),
+			Composite::Unnamed(_) => return Err(EncodeError::MismatchedValue { ty }),
+		};
+		expect_len(ty, fields.len(), values.len())?;
+		for field in fields {
+			let name = self.resolve_string(*field.name())?;
+			let (_, value) = values
+				.iter()
+				.find(|(value_name, _)| value_name == name)
+				.ok_or_else(|| EncodeError::MissingField { ty, name: name.into() })?;
+			self.encode_type(*field.ty(), value)?;
+		}
+		Ok(())
+	}
+
+	fn encode_unnamed_fields(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		fields: &[UnnamedField<CompactForm>],
+		composite: &Composite,
+	) -> Result<(), EncodeError> {
+		let values = match composite {
+			Composite::Unnamed(values) => values,
+			Composite::Named(_) => return Err(EncodeError::MismatchedValue { ty }),
+		};
+		expect_len(ty, fields.len(), values.len())?;
+		fields.iter().zip(values).try_for_each(|(field, value)| {
+			if field.is_non_zero() && matches!(value, Value::Primitive(primitive) if primitive.is_zero()) {
+				return Err(EncodeError::InvalidNonZero { ty });
+			}
+			self.encode_type(*field.ty(), value)
+		})
+	}
+
+	fn encode_primitive(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		primitive: &TypeIdPrimitive,
+		value: &Primitive,
+	) -> Result<(), EncodeError> {
+		match (primitive, value) {
+			(TypeIdPrimitive::Bool, Primitive::Bool(value)) => self.output.push(*value as u8),
+			(TypeIdPrimitive::Char, Primitive::Char(value)) => {
+				self.output.extend_from_slice(&(*value as u32).to_le_bytes())
+			}
+			(TypeIdPrimitive::Str, Primitive::Str(value)) => {
+				self.encode_compact_len(value.len());
+				self.output.extend_from_slice(value.as_bytes());
+			}
+			(TypeIdPrimitive::U8, Primitive::U8(value)) => self.output.push(*value),
+			(TypeIdPrimitive::U16, Primitive::U16(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::U32, Primitive::U32(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::U64, Primitive::U64(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::U128, Primitive::U128(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::I8, Primitive::I8(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::I16, Primitive::I16(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::I32, Primitive::I32(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::I64, Primitive::I64(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::I128, Primitive::I128(value)) => self.output.extend_from_slice(&value.to_le_bytes()),
+			(TypeIdPrimitive::F32, Primitive::F32(bytes)) => self.output.extend_from_slice(bytes),
+			(TypeIdPrimitive::F64, Primitive::F64(bytes)) => self.output.extend_from_slice(bytes),
+			(TypeIdPrimitive::Unit, Primitive::Unit) => (),
+			_ => return Err(EncodeError::MismatchedValue { ty }),
+		}
+		Ok(())
+	}
+
+	/// Encodes the length in the SCALE compact encoding.
+	fn encode_compact_len(&mut self, len: usize) {
+		let len = len as u64;
+		if len < 1 << 6 {
+			self.output.push((len as u8) << 2);
+		} else if len < 1 << 14 {
+			self.output
+				.extend_from_slice(&(((len as u16) << 2) | 0b01).to_le_bytes());
+		} else if len < 1 << 30 {
+			self.output
+				.extend_from_slice(&(((len as u32) << 2) | 0b10).to_le_bytes());
+		} else {
+			let bytes = len.to_le_bytes();
+			let num_bytes = 8 - (len.leading_zeros() / 8) as usize;
+			self.output.push((((num_bytes - 4) as u8) << 2) | 0b11);
+			self.output.extend_from_slice(&bytes[..num_bytes]);
+		}
+	}
+
+	fn resolve_string(&self, string: UntrackedSymbol<&'static str>) -> Result<&str, EncodeError> {
+		self.registry
+			.resolve_string(string)
+			.ok_or(EncodeError::UnknownString { string })
+	}
+}
+
+fn expect_len(ty: UntrackedSymbol<AnyTypeId>, expected: usize, actual: usize) -> Result<(), EncodeError> {
+	if expected != actual {
+		return Err(EncodeError::MismatchedLength { ty, expected, actual });
+	}
+	Ok(())
+}
+
+fn expect_unit(ty: UntrackedSymbol<AnyTypeId>, fields: &Composite) -> Result<(), EncodeError> {
+	match fields {
+		Composite::Named(values) if values.is_empty() => Ok(()),
+		Composite::Unnamed(values) if values.is_empty() => Ok(()),
+		_ => Err(EncodeError::MismatchedValue { ty }),
+	}
+}
+
+fn u8_variant(ty: UntrackedSymbol<AnyTypeId>, index: i128, name: &str) -> Result<u8, EncodeError> {
+	u8::try_from(index).map_err(|_| EncodeError::VariantOutOfRange { ty, name: name.into() })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		decode::{decode, DecodeError},
+		tests::registry_with,
+		Metadata,
+	};
+
+	fn assert_roundtrip<T>(value: Value, expected: &[u8])
+	where
+		T: Metadata + ?Sized + 'static,
+	{
+		let (registry, symbol) = registry_with::<T>();
+		let encoded = encode(&registry, symbol, &value).unwrap();
+		assert_eq!(encoded, expected);
+		assert_eq!(decode(&registry, symbol, &encoded), Ok(value));
+	}
+
+	fn variant(name: &str, fields: Vec<Value>) -> Value {
+		Variant {
+			name: name.into(),
+			fields: Composite::Unnamed(fields),
+		}
+		.into()
+	}
+
+	#[test]
+	fn roundtrips() {
+		assert_roundtrip::<bool>(Primitive::Bool(true).into(), &[1]);
+		assert_roundtrip::<char>(Primitive::Char('A').into(), &[0x41, 0, 0, 0]);
+		assert_roundtrip::<i16>(Primitive::I16(-2).into(), &[0xfe, 0xff]);
+		#[cfg(feature = "float")]
+		assert_roundtrip::<f32>(Primitive::F32(1.5f32.to_le_bytes()).into(), &[0, 0, 0xc0, 0x3f]);
+		#[cfg(feature = "float")]
+		assert_roundtrip::<f64>(
+			Primitive::F64((-2f64).to_le_bytes()).into(),
+			&[0, 0, 0, 0, 0, 0, 0, 0xc0],
+		);
+		assert_roundtrip::<()>(Primitive::Unit.into(), &[]);
+		assert_roundtrip::<String>(Primitive::Str("ab".into()).into(), &[2 << 2, b'a', b'b']);
+		assert_roundtrip::<[u8; 2]>(
+			Value::Array(vec![Primitive::U8(1).into(), Primitive::U8(2).into()]),
+			&[1, 2],
+		);
+		assert_roundtrip::<(bool, u8)>(
+			Value::Tuple(vec![Primitive::Bool(false).into(), Primitive::U8(9).into()]),
+			&[0, 9],
+		);
+		assert_roundtrip::<Option<u8>>(variant("Some", vec![Primitive::U8(5).into()]), &[1, 5]);
+		assert_roundtrip::<Result<(), bool>>(variant("Err", vec![Primitive::Bool(true).into()]), &[1, 1]);
+	}
+
+	#[test]
+	fn compact_lengths() {
+		let (registry, symbol) = registry_with::<[()]>();
+		let encode_len = |len: usize| {
+			let value = Value::Sequence(vec![Value::Primitive(Primitive::Unit); len]);
+			let encoded = encode(&registry, symbol, &value).unwrap();
+			assert_eq!(decode(&registry, symbol, &encoded), Ok(value));
+			encoded
+		};
+		assert_eq!(encode_len(1), vec![1 << 2]);
+		assert_eq!(encode_len(64), vec![0b0000_0001, 0b0000_0001]);
+		assert_eq!(encode_len(1 << 14), vec![0b0000_0010, 0, 1, 0]);
+	}
+
+	#[test]
+	fn named_fields_are_matched_by_name() {
+		let (registry, symbol) = registry_with::<Vec<u8>>();
+		let value = Value::Composite(Composite::Named(vec![(
+			"elems".into(),
+			Value::Sequence(vec![Primitive::U8(7).into()]),
+		)]));
+		assert_eq!(encode(&registry, symbol, &value), Ok(vec![1 << 2, 7]));
+
+		let misnamed = Value::Composite(Composite::Named(vec![("elements".into(), Value::Sequence(vec![]))]));
+		assert_eq!(
+			encode(&registry, symbol, &misnamed),
+			Err(EncodeError::MissingField {
+				ty: symbol,
+				name: "elems".into()
+			})
+		);
+	}
+
+	#[test]
+	fn maps_and_sets_are_sequences_of_their_elements() {
+		use crate::tm_std::{BTreeMap, BTreeSet};
+
+		let elems = |values| Value::Composite(Composite::Named(vec![("elems".into(), Value::Sequence(values))]));
+		assert_roundtrip::<BTreeSet<u8>>(
+			elems(vec![Primitive::U8(1).into(), Primitive::U8(2).into()]),
+			&[2 << 2, 1, 2],
+		);
+		assert_roundtrip::<BTreeMap<u8, bool>>(
+			elems(vec![Value::Tuple(vec![
+				Primitive::U8(3).into(),
+				Primitive::Bool(true).into(),
+			])]),
+			&[1 << 2, 3, 1],
+		);
+	}
+
+	#[test]
+	fn discriminants_are_encoded_at_the_width_of_the_repr() {
+		use crate::{ClikeEnumVariant, HasTypeDef, HasTypeId, Namespace, TypeDef, TypeDefClikeEnum, TypeIdCustom};
+
+		#[allow(unused)]
+		#[repr(i16)]
+		enum Signed {
+			Negative = -2,
+			Positive = 1,
+		}
+
+		impl HasTypeId for Signed {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Signed", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Signed {
+			fn type_def() -> TypeDef {
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Negative", Signed::Negative as i128),
+					ClikeEnumVariant::new("Positive", Signed::Positive as i128),
+				])
+				.with_repr::<i16>()
+				.into()
+			}
+		}
+
+		#[allow(unused)]
+		#[repr(u128)]
+		enum Wide {
+			Min = 0,
+			Max = u128::MAX,
+		}
+
+		impl HasTypeId for Wide {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Wide", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Wide {
+			fn type_def() -> TypeDef {
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Min", Wide::Min as i128),
+					ClikeEnumVariant::new("Max", Wide::Max as i128),
+				])
+				.with_repr::<u128>()
+				.into()
+			}
+		}
+
+		assert_roundtrip::<Signed>(variant("Negative", vec![]), &[0xfe, 0xff]);
+		assert_roundtrip::<Signed>(variant("Positive", vec![]), &[1, 0]);
+		assert_roundtrip::<Wide>(variant("Min", vec![]), &[0; 16]);
+		assert_roundtrip::<Wide>(variant("Max", vec![]), &[0xff; 16]);
+	}
+
+	#[test]
+	fn discriminants_out_of_range_are_rejected() {
+		use crate::{ClikeEnumVariant, HasTypeDef, HasTypeId, Namespace, TypeDef, TypeDefClikeEnum, TypeIdCustom};
+
+		#[allow(unused)]
+		enum Unrepresented {
+			Negative = -1,
+			Positive = 1,
+		}
+
+		impl HasTypeId for Unrepresented {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Unrepresented", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Unrepresented {
+			fn type_def() -> TypeDef {
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Negative", -1),
+					ClikeEnumVariant::new("Positive", 1),
+				])
+				.into()
+			}
+		}
+
+		assert_roundtrip::<Unrepresented>(variant("Positive", vec![]), &[1]);
+		let (registry, symbol) = registry_with::<Unrepresented>();
+		assert_eq!(
+			encode(&registry, symbol, &variant("Negative", vec![])),
+			Err(EncodeError::VariantOutOfRange {
+				ty: symbol,
+				name: "Negative".into()
+			})
+		);
+	}
+
+	#[test]
+	fn variants_are_encoded_by_their_indices() {
+		use crate::{
+			EnumVariantTupleStruct, EnumVariantUnit, HasTypeDef, HasTypeId, Namespace, TypeDef, TypeDefEnum,
+			TypeIdCustom, UnnamedField,
+		};
+
+		enum Indexed {}
+
+		impl HasTypeId for Indexed {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Indexed", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Indexed {
+			fn type_def() -> TypeDef {
+				TypeDefEnum::new(vec![
+					EnumVariantUnit::new("Unit").with_index(3).into(),
+					EnumVariantTupleStruct::new("Tuple", vec![UnnamedField::new(u8::meta_type())])
+						.with_index(1)
+						.into(),
+				])
+				.into()
+			}
+		}
+
+		assert_roundtrip::<Indexed>(variant("Unit", vec![]), &[3]);
+		assert_roundtrip::<Indexed>(variant("Tuple", vec![Primitive::U8(7).into()]), &[1, 7]);
+	}
+
+	#[test]
+	fn non_zero_integers() {
+		use core::num::NonZeroU16;
+
+		let non_zero = |value: u16| Value::Composite(Composite::Unnamed(vec![Primitive::U16(value).into()]));
+		assert_roundtrip::<NonZeroU16>(non_zero(258), &[2, 1]);
+		let (registry, symbol) = registry_with::<NonZeroU16>();
+		assert_eq!(
+			encode(&registry, symbol, &non_zero(0)),
+			Err(EncodeError::InvalidNonZero { ty: symbol })
+		);
+		assert_eq!(
+			decode(&registry, symbol, &[0, 0]),
+			Err(DecodeError::InvalidNonZero { offset: 0, ty: symbol })
+		);
+	}
+
+	#[test]
+	fn non_zero_fields() {
+		use crate::{HasTypeDef, HasTypeId, Namespace, TypeDef, TypeDefTupleStruct, TypeIdCustom, UnnamedField};
+
+		#[allow(unused)]
+		struct Ticket(bool, u8);
+
+		impl HasTypeId for Ticket {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Ticket", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Ticket {
+			fn type_def() -> TypeDef {
+				TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>(), UnnamedField::of::<u8>().non_zero()]).into()
+			}
+		}
+
+		let ticket = |number: u8| {
+			Value::Composite(Composite::Unnamed(vec![
+				Primitive::Bool(false).into(),
+				Primitive::U8(number).into(),
+			]))
+		};
+		assert_roundtrip::<Ticket>(ticket(7), &[0, 7]);
+		let (registry, symbol) = registry_with::<Ticket>();
+		assert_eq!(
+			encode(&registry, symbol, &ticket(0)),
+			Err(EncodeError::InvalidNonZero { ty: symbol })
+		);
+		assert_eq!(
+			decode(&registry, symbol, &[0, 0]),
+			Err(DecodeError::InvalidNonZero { offset: 1, ty: symbol })
+		);
+	}
+
+	#[test]
+	fn mismatched_values_are_rejected() {
+		let (registry, symbol) = registry_with::<u8>();
+		assert_eq!(
+			encode(&registry, symbol, &Primitive::U16(1).into()),
+			Err(EncodeError::MismatchedValue { ty: symbol })
+		);
+
+		let (registry, symbol) = registry_with::<[u8; 2]>();
+		assert_eq!(
+			encode(&registry, symbol, &Value::Array(vec![Primitive::U8(1).into()])),
+			Err(EncodeError::MismatchedLength {
+				ty: symbol,
+				expected: 2,
+				actual: 1
+			})
+		);
+
+		let (registry, symbol) = registry_with::<Option<u8>>();
+		assert_eq!(
+			encode(&registry, symbol, &variant("Nothing", vec![])),
+			Err(EncodeError::UnknownVariant {
+				ty: symbol,
+				name: "Nothing".into()
+			})
+		);
+		assert_eq!(
+			encode(&registry, symbol, &variant("None", vec![Primitive::U8(1).into()])),
+			Err(EncodeError::MismatchedValue { ty: symbol })
+		);
+
+		let mut output = vec![0xaa];
+		let (registry, symbol) = registry_with::<(u8, bool)>();
+		let value = Value::Tuple(vec![Primitive::U8(1).into(), Primitive::U8(2).into()]);
+		assert!(encode_to(&registry, symbol, &value, &mut output).is_err());
+		assert_eq!(output, vec![0xaa]);
+	}
+}
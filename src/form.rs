@@ -16,7 +16,7 @@
 
 use crate::tm_std::*;
 use crate::{interner::UntrackedSymbol, meta_type::MetaType};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Trait to control the internal structures of type identifiers and definitions.
 ///
@@ -63,3 +63,40 @@ impl Form for CompactForm {
 	type TypeId = UntrackedSymbol<AnyTypeId>;
 	type IndirectTypeId = Self::TypeId;
 }
+
+/// Owned form that stores strings inline and refers to types through plain symbols.
+///
+/// # Note
+///
+/// This form is self-contained with respect to strings and thus suitable
+/// for consumers that load type information from serialized registries
+/// without having access to the original string table.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize, Debug)]
+pub enum OwnedForm {}
+
+impl Form for OwnedForm {
+	type String = String;
+	type TypeId = UntrackedSymbol<AnyTypeId>;
+	type IndirectTypeId = Self::TypeId;
+}
+
+/// Maps the strings and type identifiers of one form to another form.
+pub trait FormMapper<S: Form, T: Form> {
+	/// Maps a string of the source form.
+	fn map_string(&mut self, string: S::String) -> T::String;
+	/// Maps a type identifier of the source form.
+	fn map_type_id(&mut self, type_id: S::TypeId) -> T::TypeId;
+	/// Maps an indirect type identifier of the source form.
+	fn map_indirect_type_id(&mut self, type_id: S::IndirectTypeId) -> T::IndirectTypeId;
+}
+
+/// Converts type identifiers and definitions from one form into another.
+///
+/// This is the generalization of `IntoCompact` for forms other than the meta form.
+pub trait MapForm<S: Form, T: Form> {
+	type Output;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>;
+}
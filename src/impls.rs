@@ -46,8 +46,84 @@ impl_metadata_for_primitives!(
 	i32 => TypeIdPrimitive::I32,
 	i64 => TypeIdPrimitive::I64,
 	i128 => TypeIdPrimitive::I128,
+	() => TypeIdPrimitive::Unit,
+	Infallible => TypeIdPrimitive::Never,
 );
 
+#[cfg(feature = "float")]
+impl_metadata_for_primitives!(
+	f32 => TypeIdPrimitive::F32,
+	f64 => TypeIdPrimitive::F64,
+);
+
+// Pointer sized integers are described by a fixed width so that the metadata does not depend on the platform.
+#[cfg(not(feature = "usize-32"))]
+impl_metadata_for_primitives!(
+	usize => TypeIdPrimitive::U64,
+	isize => TypeIdPrimitive::I64,
+);
+
+#[cfg(feature = "usize-32")]
+impl_metadata_for_primitives!(
+	usize => TypeIdPrimitive::U32,
+	isize => TypeIdPrimitive::I32,
+);
+
+// `NonZero*` integers are tuple structs of their integer within the prelude whose field is
+// marked as non-zero, which encoders and decoders enforce. Pointer sized ones have a fixed width.
+macro_rules! impl_metadata_for_non_zero {
+	( $( $t:ty => $name:expr, $inner:ty, )* ) => { $(
+		impl HasTypeId for $t {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new($name, Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for $t {
+			fn type_def() -> TypeDef {
+				TypeDefTupleStruct::new(vec![UnnamedField::of::<$inner>().non_zero()]).into()
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_non_zero!(
+	NonZeroU8 => "NonZeroU8", u8,
+	NonZeroU16 => "NonZeroU16", u16,
+	NonZeroU32 => "NonZeroU32", u32,
+	NonZeroU64 => "NonZeroU64", u64,
+	NonZeroU128 => "NonZeroU128", u128,
+	NonZeroI8 => "NonZeroI8", i8,
+	NonZeroI16 => "NonZeroI16", i16,
+	NonZeroI32 => "NonZeroI32", i32,
+	NonZeroI64 => "NonZeroI64", i64,
+	NonZeroI128 => "NonZeroI128", i128,
+);
+
+#[cfg(not(feature = "usize-32"))]
+impl_metadata_for_non_zero!(
+	NonZeroUsize => "NonZeroU64", u64,
+	NonZeroIsize => "NonZeroI64", i64,
+);
+
+#[cfg(feature = "usize-32")]
+impl_metadata_for_non_zero!(
+	NonZeroUsize => "NonZeroU32", u32,
+	NonZeroIsize => "NonZeroI32", i32,
+);
+
+impl<const INDEX: u16> HasTypeId for GenericParameter<INDEX> {
+	fn type_id() -> TypeId {
+		TypeIdParameter::new(INDEX).into()
+	}
+}
+
+impl<const INDEX: u16> HasTypeDef for GenericParameter<INDEX> {
+	fn type_def() -> TypeDef {
+		TypeDef::builtin()
+	}
+}
+
 macro_rules! impl_metadata_for_array {
 	( $( $n:expr )* ) => {
 		$(
@@ -101,7 +177,6 @@ macro_rules! impl_metadata_for_tuple {
     }
 }
 
-impl_metadata_for_tuple!();
 impl_metadata_for_tuple!(A);
 impl_metadata_for_tuple!(A, B);
 impl_metadata_for_tuple!(A, B, C);
@@ -127,10 +202,98 @@ where
 	T: Metadata + 'static,
 {
 	fn type_def() -> TypeDef {
-		TypeDefStruct::new(vec![NamedField::new("elems", MetaType::new::<[T]>())]).into()
+		TypeDef::new(
+			vec!["T"],
+			TypeDefStruct::new(vec![NamedField::new("elems", MetaType::new::<[T]>())]),
+		)
+	}
+
+	fn generic_type() -> Option<MetaType> {
+		Some(MetaType::new::<Vec<GenericParameter<0>>>())
 	}
 }
 
+// Collections are described like `Vec` as a sequence of their elements and
+// maps as a sequence of key/value pairs. The hasher of hash based collections is ignored.
+macro_rules! impl_metadata_for_sequences {
+	( $( $(#[$attr:meta])* $name:ident<T $(, $hasher:ident)?>, )* ) => { $(
+		$(#[$attr])*
+		impl<T $(, $hasher)?> HasTypeId for $name<T $(, $hasher)?>
+		where
+			T: Metadata + 'static,
+		{
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(stringify!($name), Namespace::prelude(), tuple_meta_type![T]).into()
+			}
+		}
+
+		$(#[$attr])*
+		impl<T $(, $hasher)?> HasTypeDef for $name<T $(, $hasher)?>
+		where
+			T: Metadata + 'static,
+		{
+			fn type_def() -> TypeDef {
+				TypeDef::new(
+					vec!["T"],
+					TypeDefStruct::new(vec![NamedField::new("elems", MetaType::new::<[T]>())]),
+				)
+			}
+
+			fn generic_type() -> Option<MetaType> {
+				Some(MetaType::new::<$name<GenericParameter<0>>>())
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_sequences!(
+	BTreeSet<T>,
+	VecDeque<T>,
+	BinaryHeap<T>,
+	LinkedList<T>,
+	#[cfg(feature = "std")]
+	HashSet<T, S>,
+);
+
+macro_rules! impl_metadata_for_maps {
+	( $( $(#[$attr:meta])* $name:ident<K, V $(, $hasher:ident)?>, )* ) => { $(
+		$(#[$attr])*
+		impl<K, V $(, $hasher)?> HasTypeId for $name<K, V $(, $hasher)?>
+		where
+			K: Metadata + 'static,
+			V: Metadata + 'static,
+		{
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(stringify!($name), Namespace::prelude(), tuple_meta_type!(K, V)).into()
+			}
+		}
+
+		$(#[$attr])*
+		impl<K, V $(, $hasher)?> HasTypeDef for $name<K, V $(, $hasher)?>
+		where
+			K: Metadata + 'static,
+			V: Metadata + 'static,
+		{
+			fn type_def() -> TypeDef {
+				TypeDef::new(
+					vec!["K", "V"],
+					TypeDefStruct::new(vec![NamedField::new("elems", MetaType::new::<[(K, V)]>())]),
+				)
+			}
+
+			fn generic_type() -> Option<MetaType> {
+				Some(MetaType::new::<$name<GenericParameter<0>, GenericParameter<1>>>())
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_maps!(
+	BTreeMap<K, V>,
+	#[cfg(feature = "std")]
+	HashMap<K, V, S>,
+);
+
 impl<T> HasTypeId for Option<T>
 where
 	T: Metadata + 'static,
@@ -145,11 +308,17 @@ where
 	T: Metadata + 'static,
 {
 	fn type_def() -> TypeDef {
-		TypeDefEnum::new(vec![
-			EnumVariantUnit::new("None").into(),
-			EnumVariantTupleStruct::new("Some", vec![UnnamedField::of::<T>()]).into(),
-		])
-		.into()
+		TypeDef::new(
+			vec!["T"],
+			TypeDefEnum::new(vec![
+				EnumVariantUnit::new("None").into(),
+				EnumVariantTupleStruct::new("Some", vec![UnnamedField::of::<T>()]).into(),
+			]),
+		)
+	}
+
+	fn generic_type() -> Option<MetaType> {
+		Some(MetaType::new::<Option<GenericParameter<0>>>())
 	}
 }
 
@@ -169,11 +338,89 @@ where
 	E: Metadata + 'static,
 {
 	fn type_def() -> TypeDef {
-		TypeDefEnum::new(vec![
-			EnumVariantTupleStruct::new("Ok", vec![UnnamedField::of::<T>()]).into(),
-			EnumVariantTupleStruct::new("Err", vec![UnnamedField::of::<E>()]).into(),
-		])
-		.into()
+		TypeDef::new(
+			vec!["T", "E"],
+			TypeDefEnum::new(vec![
+				EnumVariantTupleStruct::new("Ok", vec![UnnamedField::of::<T>()]).into(),
+				EnumVariantTupleStruct::new("Err", vec![UnnamedField::of::<E>()]).into(),
+			]),
+		)
+	}
+
+	fn generic_type() -> Option<MetaType> {
+		Some(MetaType::new::<Result<GenericParameter<0>, GenericParameter<1>>>())
+	}
+}
+
+macro_rules! impl_metadata_for_ranges {
+	( $( $range:ident, )* ) => { $(
+		impl<Idx> HasTypeId for $range<Idx>
+		where
+			Idx: Metadata + 'static,
+		{
+			fn type_id() -> TypeId {
+				TypeIdCustom::new(stringify!($range), Namespace::prelude(), tuple_meta_type![Idx]).into()
+			}
+		}
+
+		impl<Idx> HasTypeDef for $range<Idx>
+		where
+			Idx: Metadata + 'static,
+		{
+			fn type_def() -> TypeDef {
+				TypeDef::new(
+					vec!["Idx"],
+					TypeDefStruct::new(vec![NamedField::of::<Idx>("start"), NamedField::of::<Idx>("end")]),
+				)
+			}
+
+			fn generic_type() -> Option<MetaType> {
+				Some(MetaType::new::<$range<GenericParameter<0>>>())
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_ranges!(Range, RangeInclusive,);
+
+impl<T> HasTypeId for Bound<T>
+where
+	T: Metadata + 'static,
+{
+	fn type_id() -> TypeId {
+		TypeIdCustom::new("Bound", Namespace::prelude(), tuple_meta_type![T]).into()
+	}
+}
+
+impl<T> HasTypeDef for Bound<T>
+where
+	T: Metadata + 'static,
+{
+	fn type_def() -> TypeDef {
+		TypeDef::new(
+			vec!["T"],
+			TypeDefEnum::new(vec![
+				EnumVariantTupleStruct::new("Included", vec![UnnamedField::of::<T>()]).into(),
+				EnumVariantTupleStruct::new("Excluded", vec![UnnamedField::of::<T>()]).into(),
+				EnumVariantUnit::new("Unbounded").into(),
+			]),
+		)
+	}
+
+	fn generic_type() -> Option<MetaType> {
+		Some(MetaType::new::<Bound<GenericParameter<0>>>())
+	}
+}
+
+impl HasTypeId for Duration {
+	fn type_id() -> TypeId {
+		TypeIdCustom::new("Duration", Namespace::prelude(), vec![]).into()
+	}
+}
+
+impl HasTypeDef for Duration {
+	fn type_def() -> TypeDef {
+		TypeDefStruct::new(vec![NamedField::of::<u64>("secs"), NamedField::of::<u32>("nanos")]).into()
 	}
 }
 
@@ -193,6 +440,10 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn generic_type() -> Option<MetaType> {
+		T::generic_type()
+	}
 }
 
 impl<T> HasTypeId for &T
@@ -211,6 +462,10 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn generic_type() -> Option<MetaType> {
+		T::generic_type()
+	}
 }
 
 impl<T> HasTypeId for &mut T
@@ -229,6 +484,77 @@ where
 	fn type_def() -> TypeDef {
 		T::type_def()
 	}
+
+	fn generic_type() -> Option<MetaType> {
+		T::generic_type()
+	}
+}
+
+// Smart pointers, interior mutability and arithmetic or ordering wrappers are transparent:
+// like `Box` they are described as the type they wrap since they are encoded as their inner
+// value and only affect how the value is owned, accessed or operated on at runtime.
+macro_rules! impl_metadata_for_transparent_wrappers {
+	( $( $(#[$attr:meta])* $wrapper:ident<T $(: ?$sized:ident)?>, )* ) => { $(
+		$(#[$attr])*
+		impl<T> HasTypeId for $wrapper<T>
+		where
+			T: HasTypeId $(+ ?$sized)?,
+		{
+			fn type_id() -> TypeId {
+				T::type_id()
+			}
+		}
+
+		$(#[$attr])*
+		impl<T> HasTypeDef for $wrapper<T>
+		where
+			T: Metadata $(+ ?$sized)?,
+		{
+			fn type_def() -> TypeDef {
+				T::type_def()
+			}
+
+			fn generic_type() -> Option<MetaType> {
+				T::generic_type()
+			}
+		}
+	)* }
+}
+
+impl_metadata_for_transparent_wrappers!(
+	Rc<T: ?Sized>,
+	Arc<T: ?Sized>,
+	Cell<T: ?Sized>,
+	RefCell<T: ?Sized>,
+	#[cfg(feature = "std")]
+	Mutex<T: ?Sized>,
+	#[cfg(feature = "std")]
+	RwLock<T: ?Sized>,
+	Wrapping<T>,
+	Saturating<T>,
+	Reverse<T>,
+);
+
+impl<B> HasTypeId for Cow<'_, B>
+where
+	B: HasTypeId + ToOwned + ?Sized,
+{
+	fn type_id() -> TypeId {
+		B::type_id()
+	}
+}
+
+impl<B> HasTypeDef for Cow<'_, B>
+where
+	B: Metadata + ToOwned + ?Sized,
+{
+	fn type_def() -> TypeDef {
+		B::type_def()
+	}
+
+	fn generic_type() -> Option<MetaType> {
+		B::generic_type()
+	}
 }
 
 impl<T> HasTypeId for [T]
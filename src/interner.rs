@@ -15,18 +15,37 @@
 // limitations under the License.
 
 use crate::tm_std::*;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// A symbol that is not lifetime tracked.
 ///
 /// This can be used by self-referential types but
 /// can no longer be used to resolve instances.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+///
+/// Serializes as its plain integer identifier.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct UntrackedSymbol<T> {
 	id: NonZeroU32,
+	#[serde(skip)]
 	marker: PhantomData<fn() -> T>,
 }
 
+impl<T> UntrackedSymbol<T> {
+	/// Creates the symbol that refers to the interned entity at the given index.
+	pub(crate) fn from_index(index: usize) -> Self {
+		Self {
+			id: NonZeroU32::new((index + 1) as u32).unwrap(),
+			marker: PhantomData,
+		}
+	}
+
+	/// Returns the index of the interned entity this symbol refers to.
+	pub(crate) fn index(&self) -> usize {
+		(self.id.get() - 1) as usize
+	}
+}
+
 /// A symbol from an interner.
 ///
 /// Can be used to resolve to the associated instance.
@@ -60,7 +79,11 @@ impl<T> Symbol<'_, T> {
 	}
 }
 
+/// Interns entities and hands out symbols referring to them.
+///
+/// Serializes as the sequence of its interned entities.
 #[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
 pub struct Interner<T> {
 	#[serde(skip)]
 	map: BTreeMap<T, usize>,
@@ -80,11 +103,48 @@ where
 	}
 }
 
+impl<T> Default for Interner<T>
+where
+	T: Ord,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> Interner<T> {
+	/// Returns all interned entities in the order of their symbols.
+	pub(crate) fn elements(&self) -> &[T] {
+		&self.vec
+	}
+
+	/// Returns all entities together with the index of the symbol they resolve to.
+	///
+	/// This includes aliased entities.
+	pub(crate) fn entries(&self) -> impl Iterator<Item = (&T, usize)> {
+		self.map.iter().map(|(elem, &idx)| (elem, idx))
+	}
+}
+
+impl<'de, T> Deserialize<'de> for Interner<T>
+where
+	T: Ord + Clone + Deserialize<'de>,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let vec = Vec::<T>::deserialize(deserializer)?;
+		let map = vec.iter().cloned().enumerate().map(|(idx, elem)| (elem, idx)).collect();
+		Ok(Self { map, vec })
+	}
+}
+
 impl<T> Interner<T>
 where
 	T: Ord + Clone,
 {
-	pub fn intern_or_get(&mut self, s: T) -> (bool, Symbol<T>) {
+	pub fn intern_or_get(&mut self, s: T) -> (bool, Symbol<'_, T>) {
 		let next_id = self.vec.len();
 		let (inserted, sym_id) = match self.map.entry(s.clone()) {
 			Entry::Vacant(vacant) => {
@@ -103,13 +163,28 @@ where
 		)
 	}
 
-	pub fn get(&self, s: &T) -> Option<Symbol<T>> {
+	pub fn get(&self, s: &T) -> Option<Symbol<'_, T>> {
 		self.map.get(s).map(|&id| Symbol {
-			id: NonZeroU32::new(id as u32).unwrap(),
+			id: NonZeroU32::new((id + 1) as u32).unwrap(),
 			marker: PhantomData,
 		})
 	}
 
+	/// Makes the entity an alias of the already interned entity at the given index.
+	///
+	/// Interning the entity afterwards yields the symbol of the aliased entity.
+	/// Returns `false` and does nothing if the entity has already been interned.
+	pub(crate) fn alias(&mut self, s: T, index: usize) -> bool {
+		debug_assert!(index < self.vec.len());
+		match self.map.entry(s) {
+			Entry::Vacant(vacant) => {
+				vacant.insert(index);
+				true
+			}
+			Entry::Occupied(_) => false,
+		}
+	}
+
 	pub fn resolve(&self, sym: Symbol<T>) -> Option<&T> {
 		let idx = (sym.id.get() - 1) as usize;
 		if idx >= self.vec.len() {
@@ -153,5 +228,21 @@ mod tests {
 		assert_resolve(&mut interner, 2, ", World!");
 		assert_resolve(&mut interner, 3, "1 2 3");
 		assert_resolve(&mut interner, 4, None);
+
+		assert_eq!(interner.get(&"Hello").map(|sym| sym.id.get()), Some(1));
+		assert_eq!(interner.get(&"1 2 3").map(|sym| sym.id.get()), Some(3));
+		assert_eq!(interner.get(&"unknown"), None);
+	}
+
+	#[test]
+	fn alias() {
+		let mut interner = StringInterner::new();
+		assert_id(&mut interner, "Hello", 1);
+		assert_id(&mut interner, "World", 2);
+		assert!(interner.alias("Hi", 0));
+		assert!(!interner.alias("World", 0));
+		assert_id(&mut interner, "Hi", 1);
+		assert_id(&mut interner, "1 2 3", 3);
+		assert_eq!(interner.elements(), &["Hello", "World", "1 2 3"]);
 	}
 }
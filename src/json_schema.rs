@@ -0,0 +1,933 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of registered types as JSON Schema documents.
+//!
+//! The schemas describe the JSON representation of values as produced by `serde_json`:
+//!
+//! - Structs are objects with all fields required.
+//! - Tuples and tuple structs are arrays of fixed length with `prefixItems`.
+//!   Tuple structs with a single field are represented by their field
+//!   and unit structs as well as the unit tuple by `null`.
+//! - Enums are externally tagged by default: unit variants are strings and all other
+//!   variants are objects with the variant name as their only property.
+//!   Internally and adjacently tagged enums are objects with a tag property and
+//!   the variants of untagged enums are represented by their fields alone.
+//! - Flattened fields of structs are inlined into the enclosing object.
+//! - C-like enums are string enums of their variant names.
+//! - `Option<T>` is either `null` or `T` and `Vec<T>` is an array of `T`.
+//!   The sets, deques, heaps and lists of the prelude are arrays as well and its maps
+//!   are objects whose property values are of the value type.
+//!
+//! Custom types are defined in `$defs` under their type path and referred to through `$ref`.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, EnumTagging, EnumVariant, Metadata, NamedField, OwnedRegistry,
+	Registry, TypeDefKind, TypeId, TypeIdPrimitive, UnnamedField,
+};
+use serde::Serialize;
+
+/// The JSON Schema dialect of generated documents.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// An error that may be encountered upon exporting a JSON Schema.
+#[derive(PartialEq, Eq, Debug)]
+pub enum SchemaError {
+	/// If a type symbol does not belong to the registry.
+	UnknownType {
+		/// The unknown type symbol.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+	/// If the JSON representation of a type cannot be derived from its definition.
+	///
+	/// This is the case for unions, generic parameters, the never type and custom types with builtin definitions
+	/// as well as flattened fields and internally tagged newtype variants whose type is not a struct.
+	UnsupportedType {
+		/// The unsupported type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+}
+
+/// The primitive JSON types.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceType {
+	Null,
+	Boolean,
+	Integer,
+	Number,
+	String,
+	Array,
+	Object,
+}
+
+/// A JSON Schema.
+///
+/// Only the keywords required to describe registered types are supported.
+/// Absent keywords are not serialized.
+#[derive(PartialEq, Eq, Clone, Default, Debug, Serialize)]
+pub struct Schema {
+	/// The dialect of the schema document.
+	#[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+	pub dialect: Option<String>,
+	/// A reference to a schema defined in `$defs`.
+	#[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+	pub reference: Option<String>,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub instance_type: Option<InstanceType>,
+	#[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+	pub constant: Option<String>,
+	#[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+	pub enumeration: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub minimum: Option<i64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub maximum: Option<u64>,
+	#[serde(rename = "minLength", skip_serializing_if = "Option::is_none")]
+	pub min_length: Option<u64>,
+	#[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+	pub max_length: Option<u64>,
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub properties: BTreeMap<String, Schema>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub required: Vec<String>,
+	#[serde(rename = "additionalProperties", skip_serializing_if = "Option::is_none")]
+	pub additional_properties: Option<AdditionalProperties>,
+	#[serde(rename = "prefixItems", skip_serializing_if = "Vec::is_empty")]
+	pub prefix_items: Vec<Schema>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub items: Option<Box<Schema>>,
+	#[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
+	pub min_items: Option<u64>,
+	#[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
+	pub max_items: Option<u64>,
+	#[serde(rename = "oneOf", skip_serializing_if = "Vec::is_empty")]
+	pub one_of: Vec<Schema>,
+	#[serde(rename = "anyOf", skip_serializing_if = "Vec::is_empty")]
+	pub any_of: Vec<Schema>,
+	/// The definitions of custom types by their type paths.
+	#[serde(rename = "$defs", skip_serializing_if = "BTreeMap::is_empty")]
+	pub defs: BTreeMap<String, Schema>,
+}
+
+/// The properties of an object that are not listed in `properties`.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum AdditionalProperties {
+	/// Whether any other properties are allowed.
+	Allowed(bool),
+	/// The schema of all other properties.
+	Schema(Box<Schema>),
+}
+
+impl Schema {
+	/// Creates a schema of the given JSON type.
+	pub fn of(instance_type: InstanceType) -> Self {
+		Self {
+			instance_type: Some(instance_type),
+			..Self::default()
+		}
+	}
+
+	/// Creates a schema that refers to a definition in `$defs`.
+	pub fn reference(path: &str) -> Self {
+		Self {
+			reference: Some(format!("#/$defs/{}", encode_pointer(path))),
+			..Self::default()
+		}
+	}
+}
+
+/// Exports the JSON Schema of the given type.
+///
+/// All custom types that the type refers to are defined in `$defs`.
+pub fn schema_for<T>() -> Schema
+where
+	T: Metadata + ?Sized + 'static,
+{
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&T::meta_type());
+	schema(&registry.into(), symbol).expect("all registered types belong to the registry")
+}
+
+/// Exports the JSON Schema of the given type of the registry.
+///
+/// All custom types that the type refers to are defined in `$defs`.
+pub fn schema(registry: &OwnedRegistry, ty: UntrackedSymbol<AnyTypeId>) -> Result<Schema, SchemaError> {
+	let mut exporter = Exporter::new(registry);
+	let mut schema = exporter.type_schema(ty)?;
+	schema.dialect = Some(String::from(DIALECT));
+	schema.defs = exporter.defs;
+	Ok(schema)
+}
+
+/// Exports the definitions of all custom types of the registry.
+///
+/// The returned schema has no constraints of its own and defines all custom types in `$defs`.
+pub fn registry_schema(registry: &OwnedRegistry) -> Result<Schema, SchemaError> {
+	let mut exporter = Exporter::new(registry);
+	for (symbol, _, _) in registry.enumerate() {
+		if !registry.is_parametric(symbol) {
+			exporter.type_schema(symbol)?;
+		}
+	}
+	Ok(Schema {
+		dialect: Some(String::from(DIALECT)),
+		defs: exporter.defs,
+		..Schema::default()
+	})
+}
+
+/// Percent-encodes a type path for use within the fragment of a `$ref`.
+fn encode_pointer(path: &str) -> String {
+	let mut encoded = String::new();
+	for byte in path.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b':' => encoded.push(byte as char),
+			_ => encoded.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+	encoded
+}
+
+/// Exports the schemas of the types of a registry.
+struct Exporter<'a> {
+	registry: &'a OwnedRegistry,
+	/// The definitions of the custom types exported so far.
+	///
+	/// Definitions being exported are already present as placeholders
+	/// so that recursive types terminate.
+	defs: BTreeMap<String, Schema>,
+}
+
+impl<'a> Exporter<'a> {
+	fn new(registry: &'a OwnedRegistry) -> Self {
+		Self {
+			registry,
+			defs: BTreeMap::new(),
+		}
+	}
+
+	fn string(&self, symbol: &UntrackedSymbol<&'static str>) -> &'a str {
+		self.registry
+			.resolve_string(*symbol)
+			.expect("registries only contain valid string symbols")
+	}
+
+	/// Returns the schema of the type.
+	///
+	/// Custom types are defined in `$defs` and referred to by the returned schema.
+	fn type_schema(&mut self, ty: UntrackedSymbol<AnyTypeId>) -> Result<Schema, SchemaError> {
+		let type_id_def = self.registry.resolve(ty).ok_or(SchemaError::UnknownType { ty })?;
+		match type_id_def.id() {
+			TypeId::Primitive(primitive) => primitive_schema(primitive).ok_or(SchemaError::UnsupportedType { ty }),
+			TypeId::Slice(slice) => self.array_schema(*slice.type_param(), None),
+			TypeId::Array(array) => self.array_schema(array.type_param, Some(u64::from(array.len))),
+			TypeId::Tuple(tuple) if tuple.type_params.is_empty() => Ok(Schema::of(InstanceType::Null)),
+			TypeId::Tuple(tuple) => self.tuple_schema(tuple.type_params.iter().cloned()),
+			TypeId::Parameter(_) => Err(SchemaError::UnsupportedType { ty }),
+			TypeId::Custom(custom) => {
+				let is_prelude = custom.namespace().segments().is_empty();
+				let type_params = custom.type_params();
+				match (self.string(custom.name()), type_params.len()) {
+					("Option", 1) if is_prelude => {
+						let some = self.type_schema(type_params[0])?;
+						Ok(Schema {
+							any_of: vec![Schema::of(InstanceType::Null), some],
+							..Schema::default()
+						})
+					}
+					("Vec", 1)
+					| ("BTreeSet", 1)
+					| ("VecDeque", 1)
+					| ("BinaryHeap", 1)
+					| ("LinkedList", 1)
+					| ("HashSet", 1)
+						if is_prelude =>
+					{
+						self.array_schema(type_params[0], None)
+					}
+					// Map keys are always written as strings, so only the values are described.
+					("BTreeMap", 2) | ("HashMap", 2) if is_prelude => Ok(Schema {
+						additional_properties: Some(AdditionalProperties::Schema(Box::new(
+							self.type_schema(type_params[1])?,
+						))),
+						..Schema::of(InstanceType::Object)
+					}),
+					_ => {
+						let path = self.registry.type_path(ty).ok_or(SchemaError::UnknownType { ty })?;
+						if !self.defs.contains_key(&path) {
+							self.defs.insert(path.clone(), Schema::default());
+							let def = self.def_schema(ty, type_id_def.def().kind())?;
+							self.defs.insert(path.clone(), def);
+						}
+						Ok(Schema::reference(&path))
+					}
+				}
+			}
+		}
+	}
+
+	fn def_schema(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		kind: &TypeDefKind<CompactForm>,
+	) -> Result<Schema, SchemaError> {
+		match kind {
+			TypeDefKind::Struct(r#struct) => self.object_schema(r#struct.fields()),
+			TypeDefKind::TupleStruct(tuple_struct) => self.unnamed_fields_schema(tuple_struct.fields()),
+			TypeDefKind::ClikeEnum(clike_enum) => Ok(Schema {
+				enumeration: clike_enum
+					.variants()
+					.iter()
+					.map(|variant| String::from(self.string(variant.name())))
+					.collect(),
+				..Schema::of(InstanceType::String)
+			}),
+			TypeDefKind::Enum(r#enum) => {
+				let variants = r#enum
+					.variants()
+					.iter()
+					.map(|variant| self.variant_schema(ty, r#enum.tagging(), variant))
+					.collect::<Result<Vec<_>, _>>()?;
+				// Untagged variants are tried in order and thus may overlap.
+				if *r#enum.tagging() == EnumTagging::Untagged {
+					Ok(Schema {
+						any_of: variants,
+						..Schema::default()
+					})
+				} else {
+					Ok(Schema {
+						one_of: variants,
+						..Schema::default()
+					})
+				}
+			}
+			TypeDefKind::Builtin | TypeDefKind::Union(_) => Err(SchemaError::UnsupportedType { ty }),
+		}
+	}
+
+	fn variant_schema(
+		&mut self,
+		ty: UntrackedSymbol<AnyTypeId>,
+		tagging: &EnumTagging<CompactForm>,
+		variant: &EnumVariant<CompactForm>,
+	) -> Result<Schema, SchemaError> {
+		let name = String::from(self.string(variant.name()));
+		match (tagging, variant) {
+			(EnumTagging::External, EnumVariant::Unit(_)) => Ok(Schema {
+				constant: Some(name),
+				..Schema::of(InstanceType::String)
+			}),
+			(EnumTagging::External, _) => {
+				let fields = self.variant_fields_schema(variant)?;
+				Ok(object(vec![(name, fields)]))
+			}
+			(EnumTagging::Internal { tag }, EnumVariant::Unit(_)) => {
+				Ok(object(vec![(String::from(self.string(tag)), string_constant(name))]))
+			}
+			(EnumTagging::Internal { tag }, EnumVariant::Struct(r#struct)) => {
+				let mut properties = vec![(String::from(self.string(tag)), string_constant(name))];
+				self.push_properties(&mut properties, r#struct.fields())?;
+				Ok(object(properties))
+			}
+			(EnumTagging::Internal { tag }, EnumVariant::TupleStruct(tuple_struct)) => match tuple_struct.fields() {
+				[field] => {
+					let mut properties = vec![(String::from(self.string(tag)), string_constant(name))];
+					let fields = self.struct_fields(*field.ty())?;
+					self.push_properties(&mut properties, fields)?;
+					Ok(object(properties))
+				}
+				_ => Err(SchemaError::UnsupportedType { ty }),
+			},
+			(EnumTagging::Adjacent { tag, .. }, EnumVariant::Unit(_)) => {
+				Ok(object(vec![(String::from(self.string(tag)), string_constant(name))]))
+			}
+			(EnumTagging::Adjacent { tag, content }, _) => {
+				let fields = self.variant_fields_schema(variant)?;
+				Ok(object(vec![
+					(String::from(self.string(tag)), string_constant(name)),
+					(String::from(self.string(content)), fields),
+				]))
+			}
+			(EnumTagging::Untagged, EnumVariant::Unit(_)) => Ok(Schema::of(InstanceType::Null)),
+			(EnumTagging::Untagged, _) => self.variant_fields_schema(variant),
+		}
+	}
+
+	/// Returns the schema of the fields of a struct or tuple struct variant.
+	fn variant_fields_schema(&mut self, variant: &EnumVariant<CompactForm>) -> Result<Schema, SchemaError> {
+		match variant {
+			EnumVariant::Unit(_) => Ok(Schema::of(InstanceType::Null)),
+			EnumVariant::Struct(r#struct) => self.object_schema(r#struct.fields()),
+			EnumVariant::TupleStruct(tuple_struct) => self.unnamed_fields_schema(tuple_struct.fields()),
+		}
+	}
+
+	fn object_schema(&mut self, fields: &[NamedField<CompactForm>]) -> Result<Schema, SchemaError> {
+		let mut properties = Vec::new();
+		self.push_properties(&mut properties, fields)?;
+		Ok(object(properties))
+	}
+
+	/// Appends the schemas of the fields to the properties.
+	///
+	/// The fields of flattened fields are appended in place of the flattened field.
+	fn push_properties(
+		&mut self,
+		properties: &mut Vec<(String, Schema)>,
+		fields: &[NamedField<CompactForm>],
+	) -> Result<(), SchemaError> {
+		for field in fields {
+			if field.is_flattened() {
+				let fields = self.struct_fields(*field.ty())?;
+				self.push_properties(properties, fields)?;
+			} else {
+				let name = String::from(self.string(field.name()));
+				properties.push((name, self.type_schema(*field.ty())?));
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the fields of a struct which are inlined into an enclosing object.
+	fn struct_fields(&self, ty: UntrackedSymbol<AnyTypeId>) -> Result<&'a [NamedField<CompactForm>], SchemaError> {
+		let type_id_def = self.registry.resolve(ty).ok_or(SchemaError::UnknownType { ty })?;
+		match type_id_def.def().kind() {
+			TypeDefKind::Struct(r#struct) => Ok(r#struct.fields()),
+			_ => Err(SchemaError::UnsupportedType { ty }),
+		}
+	}
+
+	fn unnamed_fields_schema(&mut self, fields: &[UnnamedField<CompactForm>]) -> Result<Schema, SchemaError> {
+		match fields {
+			[] => Ok(Schema::of(InstanceType::Null)),
+			[field] => self.type_schema(*field.ty()),
+			_ => self.tuple_schema(fields.iter().map(|field| *field.ty())),
+		}
+	}
+
+	fn tuple_schema<I>(&mut self, elems: I) -> Result<Schema, SchemaError>
+	where
+		I: Iterator<Item = UntrackedSymbol<AnyTypeId>>,
+	{
+		let prefix_items = elems
+			.map(|elem| self.type_schema(elem))
+			.collect::<Result<Vec<_>, _>>()?;
+		let len = prefix_items.len() as u64;
+		Ok(Schema {
+			prefix_items,
+			min_items: Some(len),
+			max_items: Some(len),
+			..Schema::of(InstanceType::Array)
+		})
+	}
+
+	fn array_schema(&mut self, elem: UntrackedSymbol<AnyTypeId>, len: Option<u64>) -> Result<Schema, SchemaError> {
+		Ok(Schema {
+			items: Some(Box::new(self.type_schema(elem)?)),
+			min_items: len,
+			max_items: len,
+			..Schema::of(InstanceType::Array)
+		})
+	}
+}
+
+/// Returns the schema of an object with the given required properties and no others.
+fn object(properties: Vec<(String, Schema)>) -> Schema {
+	let required = properties.iter().map(|(name, _)| name.clone()).collect();
+	Schema {
+		properties: properties.into_iter().collect(),
+		required,
+		additional_properties: Some(AdditionalProperties::Allowed(false)),
+		..Schema::of(InstanceType::Object)
+	}
+}
+
+/// Returns the schema of a string constant.
+fn string_constant(value: String) -> Schema {
+	Schema {
+		constant: Some(value),
+		..Schema::of(InstanceType::String)
+	}
+}
+
+/// Returns the schema of the primitive or `None` if it has no values.
+fn primitive_schema(primitive: &TypeIdPrimitive) -> Option<Schema> {
+	let integer = |minimum: i64, maximum: Option<u64>| Schema {
+		minimum: Some(minimum),
+		maximum,
+		..Schema::of(InstanceType::Integer)
+	};
+	let schema = match primitive {
+		TypeIdPrimitive::Bool => Schema::of(InstanceType::Boolean),
+		TypeIdPrimitive::Char => Schema {
+			min_length: Some(1),
+			max_length: Some(1),
+			..Schema::of(InstanceType::String)
+		},
+		TypeIdPrimitive::Str => Schema::of(InstanceType::String),
+		TypeIdPrimitive::U8 => integer(0, Some(u64::from(u8::MAX))),
+		TypeIdPrimitive::U16 => integer(0, Some(u64::from(u16::MAX))),
+		TypeIdPrimitive::U32 => integer(0, Some(u64::from(u32::MAX))),
+		TypeIdPrimitive::U64 => integer(0, Some(u64::MAX)),
+		TypeIdPrimitive::U128 => integer(0, None),
+		TypeIdPrimitive::I8 => integer(i64::from(i8::MIN), Some(i8::MAX as u64)),
+		TypeIdPrimitive::I16 => integer(i64::from(i16::MIN), Some(i16::MAX as u64)),
+		TypeIdPrimitive::I32 => integer(i64::from(i32::MIN), Some(i32::MAX as u64)),
+		TypeIdPrimitive::I64 => integer(i64::MIN, Some(i64::MAX as u64)),
+		TypeIdPrimitive::I128 => Schema::of(InstanceType::Integer),
+		TypeIdPrimitive::F32 | TypeIdPrimitive::F64 => Schema::of(InstanceType::Number),
+		TypeIdPrimitive::Unit => Schema::of(InstanceType::Null),
+		TypeIdPrimitive::Never => return None,
+	};
+	Some(schema)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+	use serde_json::json;
+
+	fn to_json(schema: &Schema) -> serde_json::Value {
+		serde_json::to_value(schema).unwrap()
+	}
+
+	#[test]
+	fn structural_types() {
+		assert_eq!(
+			to_json(&schema_for::<(Option<u8>, Vec<bool>, [char; 2], ())>()),
+			json!({
+				"$schema": DIALECT,
+				"type": "array",
+				"prefixItems": [
+					{ "anyOf": [{ "type": "null" }, { "type": "integer", "minimum": 0, "maximum": 255 }] },
+					{ "type": "array", "items": { "type": "boolean" } },
+					{
+						"type": "array",
+						"items": { "type": "string", "minLength": 1, "maxLength": 1 },
+						"minItems": 2,
+						"maxItems": 2,
+					},
+					{ "type": "null" },
+				],
+				"minItems": 4,
+				"maxItems": 4,
+			})
+		);
+		let usize_max = if cfg!(feature = "usize-32") {
+			u64::from(u32::MAX)
+		} else {
+			u64::MAX
+		};
+		assert_eq!(
+			to_json(&schema_for::<(bool, usize)>()),
+			json!({
+				"$schema": DIALECT,
+				"type": "array",
+				"prefixItems": [
+					{ "type": "boolean" },
+					{ "type": "integer", "minimum": 0, "maximum": usize_max },
+				],
+				"minItems": 2,
+				"maxItems": 2,
+			})
+		);
+		#[cfg(feature = "float")]
+		assert_eq!(
+			to_json(&schema_for::<(f32, f64)>()),
+			json!({
+				"$schema": DIALECT,
+				"type": "array",
+				"prefixItems": [{ "type": "number" }, { "type": "number" }],
+				"minItems": 2,
+				"maxItems": 2,
+			})
+		);
+	}
+
+	#[test]
+	fn prelude_collections() {
+		assert_eq!(
+			to_json(&schema_for::<(BTreeMap<u8, bool>, BTreeSet<u8>, VecDeque<bool>)>()),
+			json!({
+				"$schema": DIALECT,
+				"type": "array",
+				"prefixItems": [
+					{ "type": "object", "additionalProperties": { "type": "boolean" } },
+					{ "type": "array", "items": { "type": "integer", "minimum": 0, "maximum": 255 } },
+					{ "type": "array", "items": { "type": "boolean" } },
+				],
+				"minItems": 3,
+				"maxItems": 3,
+			})
+		);
+	}
+
+	#[test]
+	fn custom_types() {
+		#[allow(unused)]
+		struct Node {
+			next: Option<Box<Node>>,
+			color: Color,
+		}
+
+		impl HasTypeId for Node {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Node", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Node {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![
+					NamedField::of::<Option<Box<Node>>>("next"),
+					NamedField::of::<Color>("color"),
+				])
+				.into()
+			}
+		}
+
+		#[allow(unused)]
+		enum Color {
+			Red,
+			Green,
+		}
+
+		impl HasTypeId for Color {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Color", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Color {
+			fn type_def() -> TypeDef {
+				TypeDefClikeEnum::new(vec![
+					ClikeEnumVariant::new("Red", 0u64),
+					ClikeEnumVariant::new("Green", 1u64),
+				])
+				.into()
+			}
+		}
+
+		assert_eq!(
+			to_json(&schema_for::<Result<Node, (u16, i8)>>()),
+			json!({
+				"$schema": DIALECT,
+				"$ref": "#/$defs/Result%3Capp::Node%2C%20%28u16%2C%20i8%29%3E",
+				"$defs": {
+					"Result<app::Node, (u16, i8)>": {
+						"oneOf": [
+							{
+								"type": "object",
+								"properties": { "Ok": { "$ref": "#/$defs/app::Node" } },
+								"required": ["Ok"],
+								"additionalProperties": false,
+							},
+							{
+								"type": "object",
+								"properties": {
+									"Err": {
+										"type": "array",
+										"prefixItems": [
+											{ "type": "integer", "minimum": 0, "maximum": 65535 },
+											{ "type": "integer", "minimum": -128, "maximum": 127 },
+										],
+										"minItems": 2,
+										"maxItems": 2,
+									},
+								},
+								"required": ["Err"],
+								"additionalProperties": false,
+							},
+						],
+					},
+					"app::Node": {
+						"type": "object",
+						"properties": {
+							"next": { "anyOf": [{ "type": "null" }, { "$ref": "#/$defs/app::Node" }] },
+							"color": { "$ref": "#/$defs/app::Color" },
+						},
+						"required": ["next", "color"],
+						"additionalProperties": false,
+					},
+					"app::Color": { "type": "string", "enum": ["Red", "Green"] },
+				},
+			})
+		);
+	}
+
+	#[test]
+	fn unit_variants_and_newtypes() {
+		#[allow(unused)]
+		enum Message {
+			Ping,
+			Text(String),
+			Move { x: i32 },
+		}
+
+		impl HasTypeId for Message {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Message", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Message {
+			fn type_def() -> TypeDef {
+				TypeDefEnum::new(vec![
+					EnumVariantUnit::new("Ping").into(),
+					EnumVariantTupleStruct::new("Text", vec![UnnamedField::of::<String>()]).into(),
+					EnumVariantStruct::new("Move", vec![NamedField::of::<i32>("x")]).into(),
+				])
+				.into()
+			}
+		}
+
+		let schema = to_json(&schema_for::<Message>());
+		assert_eq!(
+			schema["$defs"]["Message"]["oneOf"],
+			json!([
+				{ "type": "string", "const": "Ping" },
+				{
+					"type": "object",
+					"properties": { "Text": { "type": "string" } },
+					"required": ["Text"],
+					"additionalProperties": false,
+				},
+				{
+					"type": "object",
+					"properties": {
+						"Move": {
+							"type": "object",
+							"properties": { "x": { "type": "integer", "minimum": -2147483648i64, "maximum": 2147483647 } },
+							"required": ["x"],
+							"additionalProperties": false,
+						},
+					},
+					"required": ["Move"],
+					"additionalProperties": false,
+				},
+			])
+		);
+	}
+
+	#[test]
+	fn tagged_enums_and_flattened_fields() {
+		#[allow(unused)]
+		struct Point {
+			x: i8,
+		}
+
+		impl HasTypeId for Point {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Point", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Point {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![NamedField::of::<i8>("x")]).into()
+			}
+		}
+
+		#[allow(unused)]
+		enum Shape {
+			Empty,
+			Point(Point),
+			Labeled { label: String, point: Point },
+		}
+
+		impl HasTypeId for Shape {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Shape", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Shape {
+			fn type_def() -> TypeDef {
+				TypeDefEnum::new(vec![
+					EnumVariantUnit::new("Empty").into(),
+					EnumVariantTupleStruct::new("Point", vec![UnnamedField::of::<Point>()]).into(),
+					EnumVariantStruct::new(
+						"Labeled",
+						vec![
+							NamedField::of::<String>("label"),
+							NamedField::of::<Point>("point").flattened(),
+						],
+					)
+					.into(),
+				])
+				.with_tagging(EnumTagging::Internal { tag: "type" })
+				.into()
+			}
+		}
+
+		let x = json!({ "type": "integer", "minimum": -128, "maximum": 127 });
+		let schema = to_json(&schema_for::<Shape>());
+		assert_eq!(
+			schema["$defs"]["Shape"]["oneOf"],
+			json!([
+				{
+					"type": "object",
+					"properties": { "type": { "type": "string", "const": "Empty" } },
+					"required": ["type"],
+					"additionalProperties": false,
+				},
+				{
+					"type": "object",
+					"properties": { "type": { "type": "string", "const": "Point" }, "x": x },
+					"required": ["type", "x"],
+					"additionalProperties": false,
+				},
+				{
+					"type": "object",
+					"properties": {
+						"type": { "type": "string", "const": "Labeled" },
+						"label": { "type": "string" },
+						"x": x,
+					},
+					"required": ["type", "label", "x"],
+					"additionalProperties": false,
+				},
+			])
+		);
+	}
+
+	#[test]
+	fn adjacently_tagged_and_untagged_enums() {
+		#[allow(unused)]
+		enum Adjacent {
+			Empty,
+			Text(String),
+		}
+
+		impl HasTypeId for Adjacent {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Adjacent", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Adjacent {
+			fn type_def() -> TypeDef {
+				TypeDefEnum::new(vec![
+					EnumVariantUnit::new("Empty").into(),
+					EnumVariantTupleStruct::new("Text", vec![UnnamedField::of::<String>()]).into(),
+				])
+				.with_tagging(EnumTagging::Adjacent { tag: "t", content: "c" })
+				.into()
+			}
+		}
+
+		#[allow(unused)]
+		enum Untagged {
+			Empty,
+			Text(String),
+		}
+
+		impl HasTypeId for Untagged {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Untagged", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Untagged {
+			fn type_def() -> TypeDef {
+				TypeDefEnum::new(vec![
+					EnumVariantUnit::new("Empty").into(),
+					EnumVariantTupleStruct::new("Text", vec![UnnamedField::of::<String>()]).into(),
+				])
+				.with_tagging(EnumTagging::Untagged)
+				.into()
+			}
+		}
+
+		assert_eq!(
+			to_json(&schema_for::<Adjacent>())["$defs"]["Adjacent"],
+			json!({
+				"oneOf": [
+					{
+						"type": "object",
+						"properties": { "t": { "type": "string", "const": "Empty" } },
+						"required": ["t"],
+						"additionalProperties": false,
+					},
+					{
+						"type": "object",
+						"properties": { "t": { "type": "string", "const": "Text" }, "c": { "type": "string" } },
+						"required": ["t", "c"],
+						"additionalProperties": false,
+					},
+				],
+			})
+		);
+		assert_eq!(
+			to_json(&schema_for::<Untagged>())["$defs"]["Untagged"],
+			json!({ "anyOf": [{ "type": "null" }, { "type": "string" }] })
+		);
+	}
+
+	#[test]
+	fn registry_schema_defines_all_custom_types() {
+		let mut registry = Registry::new();
+		registry.register_type(&<Result<u8, bool>>::meta_type());
+		registry.register_type(&<Option<Result<u8, u8>>>::meta_type());
+		let schema = registry_schema(&registry.into()).unwrap();
+		assert_eq!(
+			schema.defs.keys().collect::<Vec<_>>(),
+			vec!["Result<u8, bool>", "Result<u8, u8>"]
+		);
+		assert_eq!(schema.reference, None);
+	}
+
+	#[test]
+	fn unions_are_unsupported() {
+		#[allow(unused)]
+		union Bits {
+			int: u32,
+		}
+
+		impl HasTypeId for Bits {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Bits", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Bits {
+			fn type_def() -> TypeDef {
+				TypeDefUnion::new(vec![NamedField::of::<u32>("int")]).into()
+			}
+		}
+
+		let mut registry = Registry::new();
+		let symbol = registry.register_type(&Bits::meta_type());
+		assert_eq!(
+			schema(&registry.into(), symbol),
+			Err(SchemaError::UnsupportedType { ty: symbol })
+		);
+	}
+
+	#[test]
+	fn never_is_unsupported() {
+		let mut registry = Registry::new();
+		let symbol = registry.register_type(&Infallible::meta_type());
+		assert_eq!(
+			schema(&registry.into(), symbol),
+			Err(SchemaError::UnsupportedType { ty: symbol })
+		);
+	}
+}
@@ -14,6 +14,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Compactly serialize meta information about types in your crate.
+//!
+//! # Features
+//!
+//! - `std` (default): Implements the metadata traits for types of `std` such as `HashMap` and `Mutex`.
+//! - `docs` (default): Captures doc comments of derived types into their type definitions.
+//! - `float` (default): Implements the metadata traits for `f32` and `f64`.
+//! - `derive`: Re-exports the derive macros of `type-metadata-derive`.
+//! - `usize-32`: Describes `usize` and `isize` as 32-bit instead of 64-bit integers.
+//!
+//! **Warning:** `usize-32` is not additive. Cargo unifies features across the dependency graph,
+//! so a single crate enabling it changes the metadata of `usize`, `isize`, `NonZeroUsize` and
+//! `NonZeroIsize` for all crates, and metadata produced with and without it is incompatible.
+//! Only the final binary should enable it, never a library.
+
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(not(feature = "std"))]
@@ -21,43 +36,42 @@ extern crate alloc;
 
 #[macro_export]
 macro_rules! tuple_meta_type {
-    ( $($ty:ty),* ) => {
-        {
-			#[cfg(not(feature = "std"))]
+	( $($ty:ty),* ) => {
+		{
 			extern crate alloc as _alloc;
-			#[cfg(not(feature = "std"))]
-			#[allow(unused_mut)]
-			let mut v = _alloc::vec![];
-
-			#[cfg(feature = "std")]
-			#[allow(unused_mut)]
-			let mut v = std::vec![];
-
-            $(
-				v.push(MetaType::new::<$ty>());
-            )*
-            v
-        }
-    }
+			_alloc::vec![
+				$(
+					$crate::MetaType::new::<$ty>(),
+				)*
+			]
+		}
+	}
 }
 
 mod tm_std;
 
+pub mod compat;
+pub mod decode;
+pub mod encode;
 pub mod form;
 mod impls;
 pub mod interner;
+pub mod json_schema;
 mod meta_type;
 mod registry;
+pub mod rust;
 mod type_def;
 mod type_id;
+pub mod typescript;
 mod utils;
+pub mod value;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
 	meta_type::MetaType,
-	registry::{IntoCompact, Registry},
+	registry::{IntoCompact, OwnedRegistry, Registry, SymbolMapping, TypeIdDef},
 	type_def::*,
 	type_id::*,
 };
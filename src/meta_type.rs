@@ -30,6 +30,8 @@ pub struct MetaType {
 	fn_type_id: fn() -> TypeId<MetaForm>,
 	/// Function pointer to type definition.
 	fn_type_def: fn() -> TypeDef<MetaForm>,
+	/// Function pointer to the generic type.
+	fn_generic_type: fn() -> Option<MetaType>,
 	// The standard type ID (ab)used in order to provide
 	// cheap implementations of the standard traits
 	// such as `PartialEq`, `PartialOrd`, `Debug` and `Hash`.
@@ -46,7 +48,7 @@ impl Eq for MetaType {}
 
 impl PartialOrd for MetaType {
 	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-		self.any_id.partial_cmp(&other.any_id)
+		Some(self.cmp(other))
 	}
 }
 
@@ -79,6 +81,7 @@ impl MetaType {
 		Self {
 			fn_type_id: <T as HasTypeId>::type_id,
 			fn_type_def: <T as HasTypeDef>::type_def,
+			fn_generic_type: <T as HasTypeDef>::generic_type,
 			any_id: AnyTypeId::of::<T>(),
 		}
 	}
@@ -98,6 +101,10 @@ impl MetaType {
 		(self.fn_type_def)()
 	}
 
+	pub fn generic_type(&self) -> Option<MetaType> {
+		(self.fn_generic_type)()
+	}
+
 	pub fn any_id(&self) -> AnyTypeId {
 		self.any_id
 	}
@@ -16,12 +16,12 @@
 
 use crate::tm_std::*;
 use crate::{
-	form::CompactForm,
+	form::{CompactForm, Form, FormMapper, MapForm, OwnedForm},
 	interner::{Interner, UntrackedSymbol},
 	meta_type::MetaType,
-	TypeDef, TypeId,
+	TypeDef, TypeId, TypeIdPrimitive,
 };
-use serde::Serialize;
+use serde::{de::Error as _, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 
 pub trait IntoCompact {
 	type Output;
@@ -29,10 +29,67 @@ pub trait IntoCompact {
 	fn into_compact(self, registry: &mut Registry) -> Self::Output;
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
-pub struct TypeIdDef {
-	id: TypeId<CompactForm>,
-	def: TypeDef<CompactForm>,
+/// A type identifier together with its associated type definition.
+///
+/// Instantiations of generic types may additionally refer to the generic type
+/// whose definition they share. Their definition is then serialized as a
+/// reference to the generic type instead of being serialized in full.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypeIdDef<F: Form = CompactForm> {
+	id: TypeId<F>,
+	def: TypeDef<F>,
+	generic: Option<F::TypeId>,
+}
+
+impl<F: Form> Serialize for TypeIdDef<F>
+where
+	F::TypeId: Serialize,
+	F::IndirectTypeId: Serialize,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_struct("TypeIdDef", 2)?;
+		state.serialize_field("id", &self.id)?;
+		match &self.generic {
+			Some(generic) => state.serialize_field("generic", generic)?,
+			None => state.serialize_field("def", &self.def)?,
+		}
+		state.end()
+	}
+}
+
+impl<F: Form> TypeIdDef<F> {
+	/// Returns the type identifier.
+	pub fn id(&self) -> &TypeId<F> {
+		&self.id
+	}
+
+	/// Returns the type definition.
+	pub fn def(&self) -> &TypeDef<F> {
+		&self.def
+	}
+
+	/// Returns the generic type whose definition is shared by this type, if any.
+	pub fn generic(&self) -> Option<&F::TypeId> {
+		self.generic.as_ref()
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for TypeIdDef<S> {
+	type Output = TypeIdDef<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeIdDef {
+			id: self.id.map_form(mapper),
+			def: self.def.map_form(mapper),
+			generic: self.generic.map(|generic| mapper.map_type_id(generic)),
+		}
+	}
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize)]
@@ -40,7 +97,29 @@ pub struct Registry {
 	string_table: Interner<&'static str>,
 	#[serde(skip)]
 	type_table: Interner<AnyTypeId>,
-	types: Vec<TypeIdDef>,
+	/// The registered types keyed by their symbols.
+	///
+	/// Types are keyed instead of pushed since the symbol of a type
+	/// is reserved before the types it refers to are registered.
+	#[serde(serialize_with = "serialize_types")]
+	types: BTreeMap<UntrackedSymbol<AnyTypeId>, TypeIdDef>,
+	/// Whether instantiations of generic types refer to shared generic definitions.
+	#[serde(skip)]
+	share_generics: bool,
+}
+
+/// Serializes the registered types as a sequence ordered by their symbols.
+fn serialize_types<S>(types: &BTreeMap<UntrackedSymbol<AnyTypeId>, TypeIdDef>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.collect_seq(types.values())
+}
+
+impl Default for Registry {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl Registry {
@@ -49,7 +128,25 @@ impl Registry {
 		Self {
 			string_table: Interner::new(),
 			type_table: Interner::new(),
-			types: vec![],
+			types: BTreeMap::new(),
+			share_generics: false,
+		}
+	}
+
+	/// Creates a new empty registry that shares the definitions of generic types.
+	///
+	/// Instantiations of generic types, e.g. `Vec<u8>` and `Vec<bool>`, then refer to the
+	/// definition of their generic type, e.g. `Vec<GenericParameter<0>>`, which is
+	/// registered only once. The field types of the generic definition refer to
+	/// the generic parameters instead of concrete types.
+	///
+	/// This shrinks the serialized registry for APIs that use many instantiations
+	/// of the same generic types. An `OwnedRegistry` deserialized from it resolves
+	/// the definitions of all instantiations as usual.
+	pub fn with_shared_generics() -> Self {
+		Self {
+			share_generics: true,
+			..Self::new()
 		}
 	}
 
@@ -79,19 +176,863 @@ impl Registry {
 	/// # Note
 	///
 	/// Due to safety requirements the returns type ID symbol cannot
-	/// be used later to resolve back to the associated type definition.
-	/// However, since this facility is going to be used for serialization
-	/// purposes this functionality isn't needed anyway.
+	/// be used to resolve back to the associated type definition
+	/// through this registry. Convert the finished registry into an
+	/// `OwnedRegistry` in order to resolve type ID symbols.
 	pub fn register_type(&mut self, ty: &MetaType) -> UntrackedSymbol<AnyTypeId> {
 		let (inserted, symbol) = self.intern_type_id(ty.any_id());
 		if inserted {
 			let compact_id = ty.type_id().into_compact(self);
 			let compact_def = ty.type_def().into_compact(self);
-			self.types.push(TypeIdDef {
-				id: compact_id,
-				def: compact_def,
-			});
+			let generic = match ty.generic_type() {
+				Some(generic) if self.share_generics && generic != *ty => Some(self.register_type(&generic)),
+				_ => None,
+			};
+			self.types.insert(
+				symbol,
+				TypeIdDef {
+					id: compact_id,
+					def: compact_def,
+					generic,
+				},
+			);
 		}
 		symbol
 	}
+
+	/// Merges the other registry into this registry.
+	///
+	/// Identical strings are deduplicated and so are types that are either
+	/// the same Rust type or whose type identifiers and definitions are identical
+	/// after merging, recursive types included. All symbols of the merged types are rewritten to refer to
+	/// the strings and types of this registry.
+	///
+	/// Returns the mapping from the symbols of the other registry to the
+	/// symbols of this registry.
+	pub fn merge(&mut self, other: Registry) -> SymbolMapping {
+		let mut mapping = SymbolMapping::default();
+		for (index, string) in other.string_table.elements().iter().enumerate() {
+			mapping
+				.strings
+				.insert(UntrackedSymbol::from_index(index), self.register_string(string));
+		}
+		let mut index = BTreeMap::<_, Vec<_>>::new();
+		for (&symbol, type_id_def) in &self.types {
+			index.entry(type_id_def.id.clone()).or_default().push(symbol);
+		}
+		let mut merger = Merger {
+			into: self,
+			from: &other,
+			mapping,
+			index,
+			in_progress: BTreeSet::new(),
+		};
+		for &symbol in other.types.keys() {
+			merger.merge_type(symbol);
+		}
+		let mapping = merger.mapping;
+		// Carry over the aliases of the other registry.
+		for (any_type_id, index) in other.type_table.entries() {
+			let symbol = mapping.types[&UntrackedSymbol::from_index(index)];
+			self.type_table.alias(*any_type_id, symbol.index());
+		}
+		mapping
+	}
+
+	/// Retains only the types reachable from the given root types.
+	///
+	/// A type is reachable if it is a root or if it is referred to by the type
+	/// identifier or definition of a reachable type, e.g. as type parameter,
+	/// field type or variant field type. All other types as well as all strings
+	/// that are no longer referred to are removed from the registry.
+	///
+	/// The remaining strings and types keep their relative order and are renumbered.
+	/// Root symbols that do not belong to this registry are ignored.
+	///
+	/// Returns the mapping from the symbols of the remaining strings and types
+	/// to their new symbols.
+	pub fn retain<I>(&mut self, roots: I) -> SymbolMapping
+	where
+		I: IntoIterator<Item = UntrackedSymbol<AnyTypeId>>,
+	{
+		let mut reachable_types = BTreeSet::new();
+		let mut reachable_strings = BTreeSet::new();
+		let mut pending = roots
+			.into_iter()
+			.filter(|root| self.types.contains_key(root))
+			.collect::<Vec<_>>();
+		while let Some(symbol) = pending.pop() {
+			if !reachable_types.insert(symbol) {
+				continue;
+			}
+			let referred = SymbolCollector::collect(&self.types[&symbol]);
+			reachable_strings.extend(referred.strings);
+			pending.extend(referred.types);
+		}
+
+		self.renumber(reachable_strings, reachable_types)
+	}
+
+	/// Sorts the strings and types of the registry into a canonical order.
+	///
+	/// The order of registered types depends on the order in which they have been
+	/// registered and on the unstable `core::any::TypeId` of their underlying Rust types.
+	/// After canonicalization strings are sorted lexicographically and types are sorted
+	/// by a structural key made up of their type path (namespace, name and type parameters)
+	/// and their definition. Thus registries of the same types serialize identically
+	/// across builds and toolchains.
+	///
+	/// Types with identical structural keys keep their relative order.
+	///
+	/// Returns the mapping from the previous symbols to the canonical symbols.
+	pub fn canonicalize(&mut self) -> SymbolMapping {
+		let mut strings = (0..self.string_table.elements().len())
+			.map(UntrackedSymbol::from_index)
+			.collect::<Vec<_>>();
+		strings.sort_by_key(|symbol| self.string_table.elements()[symbol.index()]);
+		let mut types = self
+			.types
+			.iter()
+			.map(|(&symbol, type_id_def)| {
+				let key = (
+					self.type_path(symbol),
+					type_id_def.def().clone().map_form(&mut KeyMapper(self)),
+				);
+				(key, symbol)
+			})
+			.collect::<Vec<_>>();
+		types.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+		self.renumber(strings, types.into_iter().map(|(_, symbol)| symbol))
+	}
+
+	/// Returns the human readable path of the registered type.
+	fn type_path(&self, symbol: UntrackedSymbol<AnyTypeId>) -> String {
+		type_path(symbol, &|ty| self.types[&ty].id(), &|string| {
+			self.string_table.elements()[string.index()]
+		})
+	}
+
+	/// Renumbers the given strings and types in the given order.
+	///
+	/// All other strings and types are removed from the registry.
+	/// Returns the mapping from the previous symbols to the new symbols.
+	fn renumber<S, T>(&mut self, strings: S, types: T) -> SymbolMapping
+	where
+		S: IntoIterator<Item = UntrackedSymbol<&'static str>>,
+		T: IntoIterator<Item = UntrackedSymbol<AnyTypeId>>,
+	{
+		let mut mapping = SymbolMapping::default();
+		let mut string_table = Interner::new();
+		for old in strings {
+			let string = self.string_table.elements()[old.index()];
+			let new = string_table.intern_or_get(string).1.into_untracked();
+			mapping.strings.insert(old, new);
+		}
+		let mut type_table = Interner::new();
+		let mut order = Vec::new();
+		for old in types {
+			let any_type_id = self.type_table.elements()[old.index()];
+			let new = type_table.intern_or_get(any_type_id).1.into_untracked();
+			mapping.types.insert(old, new);
+			order.push(old);
+		}
+		for (any_type_id, index) in self.type_table.entries() {
+			if let Some(new) = mapping.map_type(UntrackedSymbol::from_index(index)) {
+				type_table.alias(*any_type_id, new.index());
+			}
+		}
+		let mut remapper = Remapper(&mapping);
+		let types = order
+			.into_iter()
+			.map(|old| (mapping.types[&old], self.types[&old].clone().map_form(&mut remapper)))
+			.collect();
+
+		self.string_table = string_table;
+		self.type_table = type_table;
+		self.types = types;
+		mapping
+	}
+}
+
+/// Returns the path of the type as it is written in Rust.
+///
+/// E.g. `Option<(u8, [bool])>` or `my_crate::my_module::MyStruct<u32>`.
+/// Generic parameters are written as `$0`, `$1` and so on.
+fn type_path<'a, T, S>(symbol: UntrackedSymbol<AnyTypeId>, resolve_type: &T, resolve_string: &S) -> String
+where
+	T: Fn(UntrackedSymbol<AnyTypeId>) -> &'a TypeId<CompactForm>,
+	S: Fn(UntrackedSymbol<&'static str>) -> &'a str,
+{
+	let join = |type_params: &[UntrackedSymbol<AnyTypeId>]| {
+		type_params
+			.iter()
+			.map(|type_param| type_path(*type_param, resolve_type, resolve_string))
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+	match resolve_type(symbol) {
+		TypeId::Primitive(primitive) => String::from(primitive_name(primitive)),
+		TypeId::Custom(custom) => {
+			let mut path = custom
+				.namespace()
+				.segments()
+				.iter()
+				.chain(core::iter::once(custom.name()))
+				.map(|string| resolve_string(*string))
+				.collect::<Vec<_>>()
+				.join("::");
+			if !custom.type_params().is_empty() {
+				path = format!("{}<{}>", path, join(custom.type_params()));
+			}
+			path
+		}
+		TypeId::Slice(slice) => format!("[{}]", type_path(*slice.type_param(), resolve_type, resolve_string)),
+		TypeId::Array(array) => format!(
+			"[{}; {}]",
+			type_path(array.type_param, resolve_type, resolve_string),
+			array.len
+		),
+		TypeId::Tuple(tuple) if tuple.type_params.len() == 1 => format!("({},)", join(&tuple.type_params)),
+		TypeId::Tuple(tuple) => format!("({})", join(&tuple.type_params)),
+		TypeId::Parameter(parameter) => format!("${}", parameter.index()),
+	}
+}
+
+/// Returns the name of the primitive as it is written in Rust.
+fn primitive_name(primitive: &TypeIdPrimitive) -> &'static str {
+	match primitive {
+		TypeIdPrimitive::Bool => "bool",
+		TypeIdPrimitive::Char => "char",
+		TypeIdPrimitive::Str => "str",
+		TypeIdPrimitive::U8 => "u8",
+		TypeIdPrimitive::U16 => "u16",
+		TypeIdPrimitive::U32 => "u32",
+		TypeIdPrimitive::U64 => "u64",
+		TypeIdPrimitive::U128 => "u128",
+		TypeIdPrimitive::I8 => "i8",
+		TypeIdPrimitive::I16 => "i16",
+		TypeIdPrimitive::I32 => "i32",
+		TypeIdPrimitive::I64 => "i64",
+		TypeIdPrimitive::I128 => "i128",
+		TypeIdPrimitive::F32 => "f32",
+		TypeIdPrimitive::F64 => "f64",
+		TypeIdPrimitive::Unit => "()",
+		TypeIdPrimitive::Never => "!",
+	}
+}
+
+/// A read-only registry that owns all of its strings.
+///
+/// In contrast to the `Registry` this can be deserialized from the
+/// serialized representation of a `Registry` and serializes back into it.
+///
+/// Type ID and string symbols handed out by the `Registry` it originates from
+/// can be resolved to their associated type definitions and strings.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct OwnedRegistry {
+	string_table: Interner<String>,
+	types: Vec<TypeIdDef>,
+}
+
+impl<'de> Deserialize<'de> for OwnedRegistry {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[derive(Deserialize)]
+		struct Unchecked {
+			string_table: Interner<String>,
+			types: Vec<UncheckedTypeIdDef>,
+		}
+
+		#[derive(Deserialize)]
+		struct UncheckedTypeIdDef {
+			id: TypeId<CompactForm>,
+			#[serde(default)]
+			def: Option<TypeDef<CompactForm>>,
+			#[serde(default)]
+			generic: Option<UntrackedSymbol<AnyTypeId>>,
+		}
+
+		let Unchecked { string_table, types } = Unchecked::deserialize(deserializer)?;
+		let mut checker = SymbolChecker {
+			num_strings: string_table.elements().len(),
+			num_types: types.len(),
+			invalid: None,
+		};
+		for ty in &types {
+			ty.id.clone().map_form(&mut checker);
+			ty.def.clone().map(|def| def.map_form(&mut checker));
+			ty.generic.map(|generic| checker.map_type_id(generic));
+			if ty.def.is_some() == ty.generic.is_some() {
+				checker
+					.invalid
+					.get_or_insert("type must have either a definition or a generic type");
+			}
+		}
+		if let Some(invalid) = checker.invalid {
+			return Err(D::Error::custom(invalid));
+		}
+
+		let ids = types.iter().map(|ty| ty.id.clone()).collect::<Vec<_>>();
+		let mut instantiator = Instantiator::new(&ids);
+		if let Some(invalid) = instantiator.invalid {
+			return Err(D::Error::custom(invalid));
+		}
+		let types = types
+			.iter()
+			.map(|ty| {
+				let def = match (&ty.def, ty.generic) {
+					(Some(def), _) => def.clone(),
+					(None, Some(generic)) => {
+						let generic_def = types[generic.index()]
+							.def
+							.as_ref()
+							.ok_or("generic type must not refer to another generic type")?;
+						instantiator.instantiate(&ty.id, generic_def)?
+					}
+					(None, None) => unreachable!("checked that every type has a definition or a generic type"),
+				};
+				Ok(TypeIdDef {
+					id: ty.id.clone(),
+					def,
+					generic: ty.generic,
+				})
+			})
+			.collect::<Result<_, &'static str>>()
+			.map_err(D::Error::custom)?;
+		Ok(Self { string_table, types })
+	}
+}
+
+impl From<Registry> for OwnedRegistry {
+	fn from(registry: Registry) -> Self {
+		let mut string_table = Interner::new();
+		for string in registry.string_table.elements() {
+			string_table.intern_or_get(String::from(*string));
+		}
+		Self {
+			string_table,
+			types: registry.types.into_values().collect(),
+		}
+	}
+}
+
+impl OwnedRegistry {
+	/// Returns the compact type identifiers and definitions of the registry.
+	///
+	/// The type definition associated to a type ID symbol is
+	/// located at the index of the symbol.
+	pub fn types(&self) -> &[TypeIdDef] {
+		&self.types
+	}
+
+	/// Resolves the type ID symbol to its associated type identifier and definition.
+	///
+	/// Returns `None` if the symbol does not belong to this registry.
+	pub fn resolve(&self, symbol: UntrackedSymbol<AnyTypeId>) -> Option<&TypeIdDef> {
+		self.types.get(symbol.index())
+	}
+
+	/// Resolves the string symbol to its associated string.
+	///
+	/// Returns `None` if the symbol does not belong to this registry.
+	pub fn resolve_string(&self, symbol: UntrackedSymbol<&'static str>) -> Option<&str> {
+		self.string_table
+			.elements()
+			.get(symbol.index())
+			.map(|string| string.as_str())
+	}
+
+	/// Returns an iterator over all registered types together with their type ID symbols.
+	pub fn enumerate(
+		&self,
+	) -> impl Iterator<Item = (UntrackedSymbol<AnyTypeId>, &TypeId<CompactForm>, &TypeDef<CompactForm>)> {
+		self.types
+			.iter()
+			.enumerate()
+			.map(|(index, type_id_def)| (UntrackedSymbol::from_index(index), type_id_def.id(), type_id_def.def()))
+	}
+
+	/// Returns the path of the type as it is written in Rust.
+	///
+	/// E.g. `Option<(u8, [bool])>` or `my_crate::my_module::MyStruct<u32>`.
+	///
+	/// Returns `None` if the symbol does not belong to this registry.
+	pub fn type_path(&self, symbol: UntrackedSymbol<AnyTypeId>) -> Option<String> {
+		self.resolve(symbol)?;
+		Some(type_path(symbol, &|ty| self.types[ty.index()].id(), &|string| {
+			self.string_table.elements()[string.index()].as_str()
+		}))
+	}
+
+	/// Returns `true` if the type identifier refers to generic parameters.
+	///
+	/// Such types, e.g. `Option<$0>`, only appear within shared generic definitions
+	/// and are not instantiated themselves. Returns `false` if the symbol does not
+	/// belong to this registry.
+	pub fn is_parametric(&self, symbol: UntrackedSymbol<AnyTypeId>) -> bool {
+		match self.resolve(symbol).map(TypeIdDef::id) {
+			Some(TypeId::Parameter(_)) => true,
+			Some(id) => {
+				let mut collector = SymbolCollector::default();
+				id.clone().map_form(&mut collector);
+				collector.types.into_iter().any(|param| self.is_parametric(param))
+			}
+			None => false,
+		}
+	}
+
+	/// Returns the type identifiers and definitions of the registry in owned form.
+	///
+	/// All strings are resolved through the string table of the registry
+	/// while type references remain symbols into the registry.
+	pub fn owned_types(&self) -> Vec<TypeIdDef<OwnedForm>> {
+		let mut resolver = StringResolver {
+			string_table: &self.string_table,
+		};
+		self.types
+			.iter()
+			.cloned()
+			.map(|type_id_def| type_id_def.map_form(&mut resolver))
+			.collect()
+	}
+}
+
+/// Maps the symbols of a registry to the symbols of another registry.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SymbolMapping {
+	strings: BTreeMap<UntrackedSymbol<&'static str>, UntrackedSymbol<&'static str>>,
+	types: BTreeMap<UntrackedSymbol<AnyTypeId>, UntrackedSymbol<AnyTypeId>>,
+}
+
+impl SymbolMapping {
+	/// Returns the new symbol of the string or `None` if it has not been mapped.
+	pub fn map_string(&self, symbol: UntrackedSymbol<&'static str>) -> Option<UntrackedSymbol<&'static str>> {
+		self.strings.get(&symbol).cloned()
+	}
+
+	/// Returns the new symbol of the type or `None` if it has not been mapped.
+	pub fn map_type(&self, symbol: UntrackedSymbol<AnyTypeId>) -> Option<UntrackedSymbol<AnyTypeId>> {
+		self.types.get(&symbol).cloned()
+	}
+
+	/// Returns an iterator over all mapped string symbols as `(old, new)` pairs.
+	pub fn strings(&self) -> impl Iterator<Item = (UntrackedSymbol<&'static str>, UntrackedSymbol<&'static str>)> + '_ {
+		self.strings.iter().map(|(&old, &new)| (old, new))
+	}
+
+	/// Returns an iterator over all mapped type symbols as `(old, new)` pairs.
+	pub fn types(&self) -> impl Iterator<Item = (UntrackedSymbol<AnyTypeId>, UntrackedSymbol<AnyTypeId>)> + '_ {
+		self.types.iter().map(|(&old, &new)| (old, new))
+	}
+}
+
+/// Checks that all symbols of compact structures refer to existing entries.
+///
+/// Records a description of the first invalid symbol encountered.
+struct SymbolChecker {
+	num_strings: usize,
+	num_types: usize,
+	invalid: Option<&'static str>,
+}
+
+impl FormMapper<CompactForm, CompactForm> for SymbolChecker {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> UntrackedSymbol<&'static str> {
+		if string.index() >= self.num_strings {
+			self.invalid
+				.get_or_insert("string symbol out of bounds of the string table");
+		}
+		string
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		if type_id.index() >= self.num_types {
+			self.invalid
+				.get_or_insert("type symbol out of bounds of the type table");
+		}
+		type_id
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Resolves the string symbols of compact structures into owned strings.
+struct StringResolver<'a> {
+	string_table: &'a Interner<String>,
+}
+
+impl FormMapper<CompactForm, OwnedForm> for StringResolver<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> String {
+		self.string_table.elements()[string.index()].clone()
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		type_id
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		type_id
+	}
+}
+
+/// Rewrites the symbols of compact structures using a symbol mapping.
+///
+/// # Panics
+///
+/// If a symbol has not been mapped.
+struct Remapper<'a>(&'a SymbolMapping);
+
+impl FormMapper<CompactForm, CompactForm> for Remapper<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> UntrackedSymbol<&'static str> {
+		self.0.map_string(string).expect("encountered unmapped string symbol")
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.0.map_type(type_id).expect("encountered unmapped type symbol")
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Collects the string and type symbols referred to by compact structures.
+#[derive(Default)]
+struct SymbolCollector {
+	strings: Vec<UntrackedSymbol<&'static str>>,
+	types: Vec<UntrackedSymbol<AnyTypeId>>,
+}
+
+impl SymbolCollector {
+	/// Returns the symbols referred to by the type identifier and definition.
+	fn collect(type_id_def: &TypeIdDef) -> Self {
+		let mut collector = Self::default();
+		type_id_def.clone().map_form(&mut collector);
+		collector
+	}
+}
+
+impl FormMapper<CompactForm, CompactForm> for SymbolCollector {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> UntrackedSymbol<&'static str> {
+		self.strings.push(string);
+		string
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.types.push(type_id);
+		type_id
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Form that describes types by their structural keys used for canonicalization.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Debug)]
+enum KeyForm {}
+
+impl Form for KeyForm {
+	type String = &'static str;
+	type TypeId = String;
+	type IndirectTypeId = Self::TypeId;
+}
+
+/// Replaces the symbols of compact structures by their structural keys.
+struct KeyMapper<'a>(&'a Registry);
+
+impl FormMapper<CompactForm, KeyForm> for KeyMapper<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> &'static str {
+		self.0.string_table.elements()[string.index()]
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> String {
+		self.0.type_path(type_id)
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> String {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Merges the types of one registry into another registry.
+struct Merger<'a> {
+	into: &'a mut Registry,
+	from: &'a Registry,
+	mapping: SymbolMapping,
+	/// The types of the registry merged into keyed by their type identifiers.
+	index: BTreeMap<TypeId<CompactForm>, Vec<UntrackedSymbol<AnyTypeId>>>,
+	/// Types whose referred types are currently being merged.
+	in_progress: BTreeSet<UntrackedSymbol<AnyTypeId>>,
+}
+
+impl Merger<'_> {
+	/// Merges the type and all types it refers to and returns its new symbol.
+	fn merge_type(&mut self, symbol: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		if let Some(merged) = self.merged(symbol) {
+			self.mapping.types.insert(symbol, merged);
+			return merged;
+		}
+		let any_type_id = self.from.type_table.elements()[symbol.index()];
+		if self.in_progress.contains(&symbol) {
+			// The type refers to itself and thus requires its symbol
+			// before it can be compared to the types of this registry.
+			let (_, reserved) = self.into.intern_type_id(any_type_id);
+			self.mapping.types.insert(symbol, reserved);
+			return reserved;
+		}
+		self.in_progress.insert(symbol);
+		let type_id_def = &self.from.types[&symbol];
+		// Type identifiers refer to their type parameters only, which are merged first
+		// so that the types with the same identifier can be looked up.
+		let mut collector = SymbolCollector::default();
+		type_id_def.id.clone().map_form(&mut collector);
+		for referred in collector.types {
+			self.merge_type(referred);
+		}
+		if self.mapping.map_type(symbol).is_none() {
+			let id = type_id_def.id.clone().map_form(&mut Remapper(&self.mapping));
+			let candidates = self.index.get(&id).cloned().unwrap_or_default();
+			for candidate in candidates {
+				let mut assumed = BTreeSet::new();
+				if self.equivalent(symbol, candidate, &mut assumed) {
+					for (from, into) in assumed {
+						let any_type_id = self.from.type_table.elements()[from.index()];
+						self.into.type_table.alias(any_type_id, into.index());
+						self.mapping.types.insert(from, into);
+					}
+					self.in_progress.remove(&symbol);
+					return candidate;
+				}
+			}
+		}
+		for referred in SymbolCollector::collect(type_id_def).types {
+			self.merge_type(referred);
+		}
+		self.in_progress.remove(&symbol);
+		let reserved = match self.mapping.map_type(symbol) {
+			// The type has been found equivalent to an existing type while merging the types it refers to.
+			Some(merged) if self.into.types.contains_key(&merged) => return merged,
+			reserved => reserved,
+		};
+		let merged_def = type_id_def.clone().map_form(&mut Remapper(&self.mapping));
+		if reserved.is_none() {
+			// Types referring to this type may have been merged into an identical type meanwhile.
+			let existing = self.index.get(&merged_def.id).and_then(|candidates| {
+				candidates
+					.iter()
+					.find(|candidate| self.into.types[candidate] == merged_def)
+					.cloned()
+			});
+			if let Some(existing) = existing {
+				self.into.type_table.alias(any_type_id, existing.index());
+				self.mapping.types.insert(symbol, existing);
+				return existing;
+			}
+		}
+		let merged = reserved.unwrap_or_else(|| self.into.intern_type_id(any_type_id).1);
+		self.mapping.types.insert(symbol, merged);
+		self.index.entry(merged_def.id.clone()).or_default().push(merged);
+		self.into.types.insert(merged, merged_def);
+		merged
+	}
+
+	/// Returns the symbol of the type within the registry merged into if it is known already.
+	fn merged(&self, symbol: UntrackedSymbol<AnyTypeId>) -> Option<UntrackedSymbol<AnyTypeId>> {
+		self.mapping.map_type(symbol).or_else(|| {
+			let any_type_id = self.from.type_table.elements()[symbol.index()];
+			self.into
+				.type_table
+				.get(&any_type_id)
+				.map(|existing| existing.into_untracked())
+		})
+	}
+
+	/// Returns whether the type is structurally equivalent to the type of the registry merged into.
+	///
+	/// Types that are not merged yet are compared by their identifiers and definitions.
+	/// Pairs of types that are compared are assumed to be equivalent, so that recursive
+	/// types terminate, and are all equivalent if `true` is returned.
+	fn equivalent(
+		&self,
+		from: UntrackedSymbol<AnyTypeId>,
+		into: UntrackedSymbol<AnyTypeId>,
+		assumed: &mut BTreeSet<(UntrackedSymbol<AnyTypeId>, UntrackedSymbol<AnyTypeId>)>,
+	) -> bool {
+		if let Some(merged) = self.merged(from) {
+			return merged == into;
+		}
+		if !assumed.insert((from, into)) {
+			return true;
+		}
+		let into_def = match self.into.types.get(&into) {
+			Some(into_def) => into_def,
+			None => return false,
+		};
+		let mut from_shape = Shape {
+			strings: Some(&self.mapping),
+			types: Vec::new(),
+		};
+		let mut into_shape = Shape {
+			strings: None,
+			types: Vec::new(),
+		};
+		self.from.types[&from].clone().map_form(&mut from_shape) == into_def.clone().map_form(&mut into_shape)
+			&& from_shape
+				.types
+				.iter()
+				.zip(&into_shape.types)
+				.all(|(&from, &into)| self.equivalent(from, into, assumed))
+	}
+}
+
+/// Replaces the type symbols of compact structures by their position within them,
+/// so that structures of different registries can be compared.
+///
+/// Strings are mapped into the registry merged into if a mapping is given.
+struct Shape<'a> {
+	strings: Option<&'a SymbolMapping>,
+	types: Vec<UntrackedSymbol<AnyTypeId>>,
+}
+
+impl FormMapper<CompactForm, CompactForm> for Shape<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> UntrackedSymbol<&'static str> {
+		match self.strings {
+			Some(mapping) => mapping.map_string(string).expect("encountered unmapped string symbol"),
+			None => string,
+		}
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.types.push(type_id);
+		UntrackedSymbol::from_index(self.types.len() - 1)
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
+}
+
+/// Instantiates shared generic definitions for the types referring to them.
+///
+/// The generic parameters within a generic definition are substituted by the
+/// type parameters of the instantiation. Since the types within the instantiated
+/// definition are looked up by their type identifiers, types with identical type
+/// identifiers such as `T` and `Box<T>` are represented by the first of their symbols.
+struct Instantiator<'a> {
+	ids: &'a [TypeId<CompactForm>],
+	/// The representative symbols keyed by type identifiers with representative type parameters.
+	symbols: BTreeMap<TypeId<CompactForm>, UntrackedSymbol<AnyTypeId>>,
+	/// The representative symbol of every type.
+	representatives: Vec<Option<UntrackedSymbol<AnyTypeId>>>,
+	/// Whether the type identifier of every type refers to generic parameters.
+	parametric: Vec<bool>,
+	/// The type parameters substituted for the generic parameters while instantiating.
+	args: Option<Vec<UntrackedSymbol<AnyTypeId>>>,
+	/// The number of nested type identifiers currently being mapped.
+	depth: usize,
+	invalid: Option<&'static str>,
+}
+
+impl<'a> Instantiator<'a> {
+	/// Creates an instantiator for the types with the given type identifiers.
+	fn new(ids: &'a [TypeId<CompactForm>]) -> Self {
+		let mut instantiator = Self {
+			ids,
+			symbols: BTreeMap::new(),
+			representatives: vec![None; ids.len()],
+			parametric: vec![false; ids.len()],
+			args: None,
+			depth: 0,
+			invalid: None,
+		};
+		for index in 0..ids.len() {
+			instantiator.map_type_id(UntrackedSymbol::from_index(index));
+		}
+		instantiator
+	}
+
+	/// Returns the generic definition instantiated with the type parameters of the type identifier.
+	fn instantiate(
+		&mut self,
+		id: &TypeId<CompactForm>,
+		generic_def: &TypeDef<CompactForm>,
+	) -> Result<TypeDef<CompactForm>, &'static str> {
+		let args = match id {
+			TypeId::Custom(custom) => custom
+				.type_params()
+				.iter()
+				.map(|param| self.map_type_id(*param))
+				.collect(),
+			_ => return Err("instantiation of a generic type must be a custom type"),
+		};
+		self.args = Some(args);
+		let def = generic_def.clone().map_form(self);
+		self.args = None;
+		match self.invalid.take() {
+			Some(invalid) => Err(invalid),
+			None => Ok(def),
+		}
+	}
+}
+
+impl FormMapper<CompactForm, CompactForm> for Instantiator<'_> {
+	fn map_string(&mut self, string: UntrackedSymbol<&'static str>) -> UntrackedSymbol<&'static str> {
+		string
+	}
+
+	fn map_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		let ids = self.ids;
+		let index = type_id.index();
+		match (&self.args, &ids[index]) {
+			(Some(args), TypeId::Parameter(parameter)) => {
+				return match args.get(parameter.index() as usize) {
+					Some(arg) => *arg,
+					None => {
+						self.invalid
+							.get_or_insert("generic parameter out of bounds of the type parameters");
+						type_id
+					}
+				};
+			}
+			(Some(_), _) if !self.parametric[index] => return self.representatives[index].unwrap_or(type_id),
+			(None, _) => {
+				if let Some(representative) = self.representatives[index] {
+					return representative;
+				}
+			}
+			_ => (),
+		}
+		if self.depth > ids.len() {
+			self.invalid.get_or_insert("type identifiers must not be cyclic");
+			return type_id;
+		}
+		self.depth += 1;
+		let key = ids[index].clone().map_form(self);
+		self.depth -= 1;
+		if self.args.is_some() {
+			return match self.symbols.get(&key) {
+				Some(symbol) => *symbol,
+				None => {
+					self.invalid
+						.get_or_insert("instantiation of a generic type refers to an unregistered type");
+					type_id
+				}
+			};
+		}
+		let representative = *self.symbols.entry(key.clone()).or_insert(type_id);
+		let mut collector = SymbolCollector::default();
+		key.map_form(&mut collector);
+		self.parametric[index] = matches!(ids[index], TypeId::Parameter(_))
+			|| collector.types.iter().any(|param| self.parametric[param.index()]);
+		self.representatives[index] = Some(representative);
+		representative
+	}
+
+	fn map_indirect_type_id(&mut self, type_id: UntrackedSymbol<AnyTypeId>) -> UntrackedSymbol<AnyTypeId> {
+		self.map_type_id(type_id)
+	}
 }
@@ -0,0 +1,805 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of Rust source code for registered types.
+//!
+//! Custom types are generated as structs, tuple structs and enums within modules
+//! mirroring their `Namespace`. Types of the prelude namespace are generated at the
+//! root of the output except for `Option`, `Result`, `Vec` and the collections of
+//! `std::collections` which refer to their standard library counterparts.
+//! Strings are `String` and slices are `Vec`.
+//!
+//! All instantiations of a generic type share a single generic definition.
+//! Its type parameters are named after the `GenericParams` of the definition
+//! and otherwise `T` or `T0`, `T1`, ... The fields referring to type parameters are
+//! recovered from the instantiation in which the fewest field types match a type
+//! parameter, so the more distinct instantiations are registered the better.
+//!
+//! Fields that contain their own type by value are boxed and type parameters that
+//! no field refers to are marked by a trailing `PhantomData` field or an uninhabited variant.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, utils::is_rust_identifier, EnumVariant, NamedField, OwnedRegistry,
+	TypeDef, TypeDefKind, TypeId, TypeIdCustom, TypeIdPrimitive, UnnamedField,
+};
+
+/// An error that may be encountered upon generating Rust source code.
+#[derive(PartialEq, Eq, Debug)]
+pub enum RustError {
+	/// If a type cannot be generated from its definition.
+	///
+	/// This is the case for unions and custom types with builtin definitions.
+	UnsupportedType {
+		/// The unsupported type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+}
+
+/// Generates Rust source code for all custom types of the registry.
+///
+/// Every generated type derives the given traits.
+pub fn generate(registry: &OwnedRegistry, derives: &[&str]) -> Result<String, RustError> {
+	let generator = Generator { registry };
+	let mut families = BTreeMap::<(Vec<&str>, &str), Vec<UntrackedSymbol<AnyTypeId>>>::new();
+	for (symbol, id, _) in registry.enumerate() {
+		if let TypeId::Custom(custom) = id {
+			if generator.std_type(custom).is_none() && !registry.is_parametric(symbol) {
+				let namespace = generator.segments(custom);
+				let name = generator.string(custom.name());
+				families.entry((namespace, name)).or_default().push(symbol);
+			}
+		}
+	}
+
+	let mut modules = BTreeMap::<Vec<&str>, Vec<String>>::new();
+	for ((namespace, name), instances) in families {
+		let mut item = generator.item(name, namespace.len(), &instances)?;
+		if !derives.is_empty() {
+			item = format!("#[derive({})]\n{}", derives.join(", "), item);
+		}
+		modules.entry(namespace).or_default().push(item);
+	}
+
+	let mut output = String::new();
+	render_module(&mut output, &modules, &[]);
+	Ok(output)
+}
+
+/// Renders the items of the module and all of its submodules.
+fn render_module(output: &mut String, modules: &BTreeMap<Vec<&str>, Vec<String>>, path: &[&str]) {
+	let indent = "\t".repeat(path.len());
+	let mut first = true;
+	for item in modules.get(path).into_iter().flatten() {
+		if !first {
+			output.push('\n');
+		}
+		first = false;
+		for line in item.lines() {
+			output.push_str(&indent);
+			output.push_str(line);
+			output.push('\n');
+		}
+	}
+	let submodules = modules
+		.keys()
+		.filter(|namespace| namespace.len() > path.len() && namespace.starts_with(path))
+		.map(|namespace| namespace[path.len()])
+		.collect::<BTreeSet<_>>();
+	for submodule in submodules {
+		if !first {
+			output.push('\n');
+		}
+		first = false;
+		output.push_str(&format!("{}pub mod {} {{\n", indent, submodule));
+		let mut subpath = path.to_vec();
+		subpath.push(submodule);
+		render_module(output, modules, &subpath);
+		output.push_str(&indent);
+		output.push_str("}\n");
+	}
+}
+
+/// The context in which field types are rendered.
+struct Scope<'s> {
+	/// The type whose definition is rendered.
+	owner: UntrackedSymbol<AnyTypeId>,
+	/// The type path of the owner.
+	owner_path: String,
+	/// The depth of the module of the owner.
+	depth: usize,
+	/// The type paths of the type parameters of the owner together with their names.
+	params: &'s [(String, String)],
+}
+
+/// The type parameters substituted while rendering a definition.
+#[derive(Default)]
+struct Substitutions {
+	/// The number of field types matching a type parameter.
+	count: usize,
+	/// The indices of the type parameters that are referred to.
+	params: BTreeSet<usize>,
+}
+
+/// Generates the Rust source code of the types of a registry.
+struct Generator<'a> {
+	registry: &'a OwnedRegistry,
+}
+
+impl<'a> Generator<'a> {
+	fn string(&self, symbol: &UntrackedSymbol<&'static str>) -> &'a str {
+		self.registry
+			.resolve_string(*symbol)
+			.expect("registries only contain valid string symbols")
+	}
+
+	fn resolve(&self, symbol: UntrackedSymbol<AnyTypeId>) -> (&'a TypeId<CompactForm>, &'a TypeDef<CompactForm>) {
+		let type_id_def = self
+			.registry
+			.resolve(symbol)
+			.expect("registries only contain valid type symbols");
+		(type_id_def.id(), type_id_def.def())
+	}
+
+	/// Returns the type path of the type.
+	///
+	/// Types are compared by their paths since transparent types such as `Box<T>`
+	/// are registered under symbols distinct from the types they wrap.
+	fn type_path(&self, symbol: UntrackedSymbol<AnyTypeId>) -> String {
+		self.registry
+			.type_path(symbol)
+			.expect("registries only contain valid type symbols")
+	}
+
+	fn segments(&self, custom: &TypeIdCustom<CompactForm>) -> Vec<&'a str> {
+		custom
+			.namespace()
+			.segments()
+			.iter()
+			.map(|segment| self.string(segment))
+			.collect()
+	}
+
+	/// Returns the name of the standard library type the custom type refers to.
+	fn std_type(&self, custom: &TypeIdCustom<CompactForm>) -> Option<&'static str> {
+		if !custom.namespace().segments().is_empty() {
+			return None;
+		}
+		match (self.string(custom.name()), custom.type_params().len()) {
+			("Option", 1) => Some("Option"),
+			("Vec", 1) => Some("Vec"),
+			("Result", 2) => Some("Result"),
+			("BTreeSet", 1) => Some("std::collections::BTreeSet"),
+			("VecDeque", 1) => Some("std::collections::VecDeque"),
+			("BinaryHeap", 1) => Some("std::collections::BinaryHeap"),
+			("LinkedList", 1) => Some("std::collections::LinkedList"),
+			("HashSet", 1) => Some("std::collections::HashSet"),
+			("BTreeMap", 2) => Some("std::collections::BTreeMap"),
+			("HashMap", 2) => Some("std::collections::HashMap"),
+			_ => None,
+		}
+	}
+
+	/// Generates the generic definition shared by the given instantiations of a custom type.
+	fn item(&self, name: &str, depth: usize, instances: &[UntrackedSymbol<AnyTypeId>]) -> Result<String, RustError> {
+		// Instances with equal type parameters cannot tell them apart
+		// and are only considered if there are no other instances.
+		let is_distinct = |instance: &&UntrackedSymbol<AnyTypeId>| match self.resolve(**instance).0 {
+			TypeId::Custom(custom) => {
+				let paths = custom
+					.type_params()
+					.iter()
+					.map(|type_param| self.type_path(*type_param))
+					.collect::<BTreeSet<_>>();
+				paths.len() == custom.type_params().len()
+			}
+			_ => false,
+		};
+		let mut candidates = instances.iter().filter(is_distinct).collect::<Vec<_>>();
+		if candidates.is_empty() {
+			candidates = instances.iter().collect();
+		}
+		let mut best: Option<(usize, String)> = None;
+		for &instance in candidates {
+			let (id, def) = self.resolve(instance);
+			let type_params = match id {
+				TypeId::Custom(custom) => custom.type_params(),
+				_ => unreachable!("instances are custom types"),
+			};
+			let names = self.param_names(def, type_params.len());
+			let params = type_params
+				.iter()
+				.map(|type_param| self.type_path(*type_param))
+				.zip(names.iter().cloned())
+				.collect::<Vec<_>>();
+			let scope = Scope {
+				owner: instance,
+				owner_path: self.type_path(instance),
+				depth,
+				params: &params,
+			};
+			let mut substitutions = Substitutions::default();
+			let mut body = self.body(def, &scope, None, &mut substitutions)?;
+			let unused = names
+				.iter()
+				.enumerate()
+				.filter(|(index, _)| !substitutions.params.contains(index))
+				.map(|(_, name)| name.as_str())
+				.collect::<Vec<_>>();
+			if !unused.is_empty() {
+				let marker = match unused.as_slice() {
+					[name] => format!("std::marker::PhantomData<{}>", name),
+					_ => format!("std::marker::PhantomData<({})>", unused.join(", ")),
+				};
+				body = self.body(def, &scope, Some(&marker), &mut Substitutions::default())?;
+			}
+			let generics = if names.is_empty() {
+				String::new()
+			} else {
+				format!("<{}>", names.join(", "))
+			};
+			let keyword = match def.kind() {
+				TypeDefKind::Enum(_) | TypeDefKind::ClikeEnum(_) => "enum",
+				_ => "struct",
+			};
+			let repr = match def.kind() {
+				TypeDefKind::ClikeEnum(clike_enum) => clike_enum
+					.repr()
+					.map(|repr| format!("#[repr({})]\n", primitive_type(repr)))
+					.unwrap_or_default(),
+				_ => String::new(),
+			};
+			let item = format!("{}pub {} {}{}{}", repr, keyword, name, generics, body);
+			let is_better = match &best {
+				Some((fewest, _)) => substitutions.count < *fewest,
+				None => true,
+			};
+			if is_better {
+				best = Some((substitutions.count, item));
+			}
+		}
+		Ok(best.map(|(_, item)| item).unwrap_or_default())
+	}
+
+	/// Returns the names of the type parameters of the definition.
+	///
+	/// Recorded names which are not identifiers, e.g. of associated types such as `C::Balance`,
+	/// are replaced by numbered names.
+	fn param_names(&self, def: &TypeDef<CompactForm>, arity: usize) -> Vec<String> {
+		let params = def
+			.generic_params()
+			.params()
+			.iter()
+			.map(|param| String::from(self.string(param.name())))
+			.collect::<Vec<_>>();
+		if params.len() == arity && params.iter().all(|name| is_rust_identifier(name)) {
+			return params;
+		}
+		match arity {
+			1 => vec![String::from("T")],
+			_ => (0..arity).map(|index| format!("T{}", index)).collect(),
+		}
+	}
+
+	/// Returns the body of the definition following the name and generics of the item.
+	///
+	/// The marker of unused type parameters is appended as a field or an uninhabited variant.
+	fn body(
+		&self,
+		def: &TypeDef<CompactForm>,
+		scope: &Scope,
+		marker: Option<&str>,
+		substitutions: &mut Substitutions,
+	) -> Result<String, RustError> {
+		let body = match def.kind() {
+			TypeDefKind::Struct(r#struct) if r#struct.fields().is_empty() && marker.is_none() => String::from(" {}"),
+			TypeDefKind::Struct(r#struct) => {
+				let mut fields = self.named_fields(r#struct.fields(), scope, substitutions);
+				fields.extend(marker.map(|marker| format!("_marker: {}", marker)));
+				let fields = fields.iter().map(|field| format!("pub {},", field)).collect::<Vec<_>>();
+				format!(" {{\n{}}}", indent(&fields))
+			}
+			TypeDefKind::TupleStruct(tuple_struct) if tuple_struct.fields().is_empty() && marker.is_none() => {
+				String::from(";")
+			}
+			TypeDefKind::TupleStruct(tuple_struct) => {
+				let mut fields = self.unnamed_fields(tuple_struct.fields(), scope, substitutions);
+				fields.extend(marker.map(String::from));
+				let fields = fields.iter().map(|field| format!("pub {}", field)).collect::<Vec<_>>();
+				format!("({});", fields.join(", "))
+			}
+			TypeDefKind::ClikeEnum(clike_enum) => {
+				let (_, signed) = clike_enum.discriminant_layout();
+				let variants = clike_enum
+					.variants()
+					.iter()
+					.map(|variant| {
+						let name = self.string(variant.name());
+						match variant.discriminant() {
+							discriminant if signed => format!("{} = {},", name, discriminant),
+							discriminant => format!("{} = {},", name, discriminant as u128),
+						}
+					})
+					.collect::<Vec<_>>();
+				format!(" {{\n{}}}", indent(&variants))
+			}
+			TypeDefKind::Enum(r#enum) => {
+				let mut variants = r#enum
+					.variants()
+					.iter()
+					.map(|variant| {
+						let name = self.string(variant.name());
+						match variant {
+							EnumVariant::Unit(_) => format!("{},", name),
+							EnumVariant::Struct(r#struct) => {
+								let fields = self.named_fields(r#struct.fields(), scope, substitutions);
+								format!("{} {{ {} }},", name, fields.join(", "))
+							}
+							EnumVariant::TupleStruct(tuple_struct) => {
+								let fields = self.unnamed_fields(tuple_struct.fields(), scope, substitutions);
+								format!("{}({}),", name, fields.join(", "))
+							}
+						}
+					})
+					.collect::<Vec<_>>();
+				variants.extend(marker.map(|marker| format!("_Marker(std::convert::Infallible, {}),", marker)));
+				format!(" {{\n{}}}", indent(&variants))
+			}
+			TypeDefKind::Builtin | TypeDefKind::Union(_) => return Err(RustError::UnsupportedType { ty: scope.owner }),
+		};
+		Ok(body)
+	}
+
+	/// Returns the named fields as `name: Type`.
+	fn named_fields(
+		&self,
+		fields: &[NamedField<CompactForm>],
+		scope: &Scope,
+		substitutions: &mut Substitutions,
+	) -> Vec<String> {
+		fields
+			.iter()
+			.map(|field| {
+				let ty = self.field_type(*field.ty(), scope, substitutions);
+				format!("{}: {}", self.string(field.name()), ty)
+			})
+			.collect()
+	}
+
+	fn unnamed_fields(
+		&self,
+		fields: &[UnnamedField<CompactForm>],
+		scope: &Scope,
+		substitutions: &mut Substitutions,
+	) -> Vec<String> {
+		fields
+			.iter()
+			.map(|field| self.field_type(*field.ty(), scope, substitutions))
+			.collect()
+	}
+
+	/// Returns the type of a field, boxed if it contains the owner by value.
+	fn field_type(
+		&self,
+		symbol: UntrackedSymbol<AnyTypeId>,
+		scope: &Scope,
+		substitutions: &mut Substitutions,
+	) -> String {
+		let ty = self.type_expr(symbol, scope, substitutions);
+		if self.contains_by_value(symbol, &scope.owner_path, &mut BTreeSet::new()) {
+			format!("Box<{}>", ty)
+		} else {
+			ty
+		}
+	}
+
+	/// Returns `true` if values of the type contain values of the target type
+	/// without indirection, e.g. through fields or tuples but not through slices.
+	fn contains_by_value(
+		&self,
+		symbol: UntrackedSymbol<AnyTypeId>,
+		target: &str,
+		visited: &mut BTreeSet<UntrackedSymbol<AnyTypeId>>,
+	) -> bool {
+		if self.type_path(symbol) == target {
+			return true;
+		}
+		if !visited.insert(symbol) {
+			return false;
+		}
+		let (id, def) = self.resolve(symbol);
+		let contained = match id {
+			TypeId::Primitive(_) | TypeId::Parameter(_) | TypeId::Slice(_) => Vec::new(),
+			TypeId::Array(array) => vec![array.type_param],
+			TypeId::Tuple(tuple) => tuple.type_params.clone(),
+			// Only `Option` and `Result` contain their type parameters without indirection.
+			TypeId::Custom(custom) if !matches!(self.std_type(custom), None | Some("Option") | Some("Result")) => {
+				Vec::new()
+			}
+			TypeId::Custom(_) => match def.kind() {
+				TypeDefKind::Builtin | TypeDefKind::ClikeEnum(_) => Vec::new(),
+				TypeDefKind::Struct(r#struct) => r#struct.fields().iter().map(|field| *field.ty()).collect(),
+				TypeDefKind::Union(union) => union.fields().iter().map(|field| *field.ty()).collect(),
+				TypeDefKind::TupleStruct(tuple_struct) => {
+					tuple_struct.fields().iter().map(|field| *field.ty()).collect()
+				}
+				TypeDefKind::Enum(r#enum) => r#enum
+					.variants()
+					.iter()
+					.flat_map(|variant| match variant {
+						EnumVariant::Unit(_) => Vec::new(),
+						EnumVariant::Struct(r#struct) => r#struct.fields().iter().map(|field| *field.ty()).collect(),
+						EnumVariant::TupleStruct(tuple_struct) => {
+							tuple_struct.fields().iter().map(|field| *field.ty()).collect()
+						}
+					})
+					.collect(),
+			},
+		};
+		contained
+			.into_iter()
+			.any(|contained| self.contains_by_value(contained, target, visited))
+	}
+
+	/// Returns the Rust type expression of the type.
+	///
+	/// Type parameters of the scope are substituted by their names.
+	fn type_expr(
+		&self,
+		symbol: UntrackedSymbol<AnyTypeId>,
+		scope: &Scope,
+		substitutions: &mut Substitutions,
+	) -> String {
+		let path = self.type_path(symbol);
+		if let Some(index) = scope.params.iter().position(|(param, _)| *param == path) {
+			substitutions.count += 1;
+			substitutions.params.insert(index);
+			return scope.params[index].1.clone();
+		}
+		let mut join = |type_params: &[UntrackedSymbol<AnyTypeId>]| {
+			type_params
+				.iter()
+				.map(|type_param| self.type_expr(*type_param, scope, substitutions))
+				.collect::<Vec<_>>()
+				.join(", ")
+		};
+		match self.resolve(symbol).0 {
+			TypeId::Primitive(primitive) => String::from(primitive_type(primitive)),
+			TypeId::Slice(slice) => format!("Vec<{}>", join(&[*slice.type_param()])),
+			TypeId::Array(array) => format!("[{}; {}]", join(&[array.type_param]), array.len),
+			TypeId::Tuple(tuple) if tuple.type_params.len() == 1 => format!("({},)", join(&tuple.type_params)),
+			TypeId::Tuple(tuple) => format!("({})", join(&tuple.type_params)),
+			TypeId::Parameter(parameter) => {
+				let index = usize::from(parameter.index());
+				match scope.params.get(index) {
+					Some((_, name)) => {
+						substitutions.params.insert(index);
+						name.clone()
+					}
+					None => format!("T{}", index),
+				}
+			}
+			TypeId::Custom(custom) => {
+				let path = match self.std_type(custom) {
+					Some(std_type) => String::from(std_type),
+					None => {
+						let mut path = "super::".repeat(scope.depth);
+						for segment in self.segments(custom) {
+							path.push_str(segment);
+							path.push_str("::");
+						}
+						path.push_str(self.string(custom.name()));
+						path
+					}
+				};
+				if custom.type_params().is_empty() {
+					path
+				} else {
+					format!("{}<{}>", path, join(custom.type_params()))
+				}
+			}
+		}
+	}
+}
+
+/// Returns the lines indented by one level.
+fn indent(lines: &[String]) -> String {
+	lines.iter().map(|line| format!("\t{}\n", line)).collect()
+}
+
+/// Returns the Rust type of the primitive.
+fn primitive_type(primitive: &TypeIdPrimitive) -> &'static str {
+	match primitive {
+		TypeIdPrimitive::Bool => "bool",
+		TypeIdPrimitive::Char => "char",
+		TypeIdPrimitive::Str => "String",
+		TypeIdPrimitive::U8 => "u8",
+		TypeIdPrimitive::U16 => "u16",
+		TypeIdPrimitive::U32 => "u32",
+		TypeIdPrimitive::U64 => "u64",
+		TypeIdPrimitive::U128 => "u128",
+		TypeIdPrimitive::I8 => "i8",
+		TypeIdPrimitive::I16 => "i16",
+		TypeIdPrimitive::I32 => "i32",
+		TypeIdPrimitive::I64 => "i64",
+		TypeIdPrimitive::I128 => "i128",
+		TypeIdPrimitive::F32 => "f32",
+		TypeIdPrimitive::F64 => "f64",
+		TypeIdPrimitive::Unit => "()",
+		TypeIdPrimitive::Never => "std::convert::Infallible",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	#[allow(unused)]
+	struct Node {
+		next: Option<Box<Node>>,
+		children: Vec<Node>,
+		shape: Shape,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::new(vec!["app", "graph"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<Option<Box<Node>>>("next"),
+				NamedField::of::<Vec<Node>>("children"),
+				NamedField::of::<Shape>("shape"),
+			])
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	enum Shape {
+		Empty,
+		Circle(u8),
+		Rect { w: u8, h: u8 },
+	}
+
+	impl HasTypeId for Shape {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Shape", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Shape {
+		fn type_def() -> TypeDef {
+			TypeDefEnum::new(vec![
+				EnumVariantUnit::new("Empty").into(),
+				EnumVariantTupleStruct::new("Circle", vec![UnnamedField::of::<u8>()]).into(),
+				EnumVariantStruct::new("Rect", vec![NamedField::of::<u8>("w"), NamedField::of::<u8>("h")]).into(),
+			])
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	#[repr(u16)]
+	enum Color {
+		Red = 1,
+		Green = 4,
+	}
+
+	impl HasTypeId for Color {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Color", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Color {
+		fn type_def() -> TypeDef {
+			TypeDefClikeEnum::new(vec![
+				ClikeEnumVariant::new("Red", 1u64),
+				ClikeEnumVariant::new("Green", 4u64),
+			])
+			.with_repr::<u16>()
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	struct Wrapper<T> {
+		value: T,
+		count: u8,
+	}
+
+	impl<T: Metadata + 'static> HasTypeId for Wrapper<T> {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Wrapper", Namespace::prelude(), tuple_meta_type!(T)).into()
+		}
+	}
+
+	impl<T: Metadata + 'static> HasTypeDef for Wrapper<T> {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::of::<T>("value"), NamedField::of::<u8>("count")]).into()
+		}
+	}
+
+	#[allow(unused)]
+	struct Pair<A, B>(A, [B; 2], Color);
+
+	impl<A: Metadata + 'static, B: Metadata + 'static> HasTypeId for Pair<A, B> {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Pair", Namespace::new(vec!["app"]).unwrap(), tuple_meta_type!(A, B)).into()
+		}
+	}
+
+	impl<A: Metadata + 'static, B: Metadata + 'static> HasTypeDef for Pair<A, B> {
+		fn type_def() -> TypeDef {
+			TypeDef::new(
+				vec!["Left", "Right"],
+				TypeDefTupleStruct::new(vec![
+					UnnamedField::of::<A>(),
+					UnnamedField::of::<[B; 2]>(),
+					UnnamedField::of::<Color>(),
+				]),
+			)
+		}
+	}
+
+	#[test]
+	fn generate_modules_and_items() {
+		let mut registry = Registry::new();
+		registry.register_type(&Node::meta_type());
+		registry.register_type(&<Result<Color, (String,)>>::meta_type());
+		let expected = r#"pub mod app {
+	#[derive(Debug, Clone)]
+	#[repr(u16)]
+	pub enum Color {
+		Red = 1,
+		Green = 4,
+	}
+
+	#[derive(Debug, Clone)]
+	pub enum Shape {
+		Empty,
+		Circle(u8),
+		Rect { w: u8, h: u8 },
+	}
+
+	pub mod graph {
+		#[derive(Debug, Clone)]
+		pub struct Node {
+			pub next: Box<Option<super::super::app::graph::Node>>,
+			pub children: Vec<super::super::app::graph::Node>,
+			pub shape: super::super::app::Shape,
+		}
+	}
+}
+"#;
+		assert_eq!(
+			generate(&registry.into(), &["Debug", "Clone"]),
+			Ok(String::from(expected))
+		);
+	}
+
+	#[allow(unused)]
+	struct Index {
+		entries: BTreeMap<String, Vec<Index>>,
+		pending: VecDeque<u8>,
+	}
+
+	impl HasTypeId for Index {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Index", Namespace::prelude(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Index {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<BTreeMap<String, Vec<Index>>>("entries"),
+				NamedField::of::<VecDeque<u8>>("pending"),
+			])
+			.into()
+		}
+	}
+
+	#[test]
+	fn generate_std_collections() {
+		let mut registry = Registry::new();
+		registry.register_type(&Index::meta_type());
+		let expected = r#"pub struct Index {
+	pub entries: std::collections::BTreeMap<String, Vec<Index>>,
+	pub pending: std::collections::VecDeque<u8>,
+}
+"#;
+		assert_eq!(generate(&registry.into(), &[]), Ok(String::from(expected)));
+	}
+
+	#[test]
+	fn generate_generic_items() {
+		let mut registry = Registry::new();
+		registry.register_type(&<Wrapper<u8>>::meta_type());
+		registry.register_type(&<Wrapper<bool>>::meta_type());
+		registry.register_type(&<Pair<u8, u8>>::meta_type());
+		registry.register_type(&<Pair<Wrapper<u8>, u16>>::meta_type());
+		let expected = r#"pub struct Wrapper<T> {
+	pub value: T,
+	pub count: u8,
+}
+
+pub mod app {
+	#[repr(u16)]
+	pub enum Color {
+		Red = 1,
+		Green = 4,
+	}
+
+	pub struct Pair<Left, Right>(pub Left, pub [Right; 2], pub super::app::Color);
+}
+"#;
+		assert_eq!(generate(&registry.into(), &[]), Ok(String::from(expected)));
+	}
+
+	#[allow(unused)]
+	enum Keyed<K, V> {
+		Entry(V),
+		Empty(core::marker::PhantomData<K>),
+	}
+
+	impl<K: Metadata + 'static, V: Metadata + 'static> HasTypeId for Keyed<K, V> {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Keyed", Namespace::prelude(), tuple_meta_type!(K, V)).into()
+		}
+	}
+
+	impl<K: Metadata + 'static, V: Metadata + 'static> HasTypeDef for Keyed<K, V> {
+		fn type_def() -> TypeDef {
+			TypeDef::new(
+				vec!["Key", "Value"],
+				TypeDefEnum::new(vec![
+					EnumVariantTupleStruct::new("Entry", vec![UnnamedField::of::<GenericParameter<1>>()]).into(),
+					EnumVariantUnit::new("Empty").into(),
+				]),
+			)
+		}
+	}
+
+	#[test]
+	fn generate_unused_type_params() {
+		let mut registry = Registry::new();
+		registry.register_type(&<Keyed<u8, bool>>::meta_type());
+		registry.register_type(&<Pair<u8, u8>>::meta_type());
+		let expected = r#"pub enum Keyed<Key, Value> {
+	Entry(Value),
+	Empty,
+	_Marker(std::convert::Infallible, std::marker::PhantomData<Key>),
+}
+
+pub mod app {
+	#[repr(u16)]
+	pub enum Color {
+		Red = 1,
+		Green = 4,
+	}
+
+	pub struct Pair<Left, Right>(pub Left, pub [Left; 2], pub super::app::Color, pub std::marker::PhantomData<Right>);
+}
+"#;
+		assert_eq!(generate(&registry.into(), &[]), Ok(String::from(expected)));
+	}
+}
@@ -14,9 +14,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::interner::UntrackedSymbol;
+use crate::tm_std::AnyTypeId;
 use crate::*;
 use core::marker::PhantomData;
 
+/// Registers the given type with a new registry and returns the owned registry along with its symbol.
+pub(crate) fn registry_with<T>() -> (OwnedRegistry, UntrackedSymbol<AnyTypeId>)
+where
+	T: Metadata + ?Sized + 'static,
+{
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&T::meta_type());
+	(registry.into(), symbol)
+}
+
 fn assert_type_id<T, E>(expected: E)
 where
 	T: HasTypeId + ?Sized,
@@ -28,7 +40,7 @@ where
 macro_rules! assert_type_id {
 	( $ty:ty, $expected:expr ) => {{
 		assert_type_id::<$ty, _>($expected)
-		}};
+	}};
 }
 
 #[test]
@@ -42,6 +54,38 @@ fn primitives() {
 	assert_type_id!(&String, TypeIdPrimitive::Str);
 	assert_type_id!([bool], TypeIdSlice::new(bool::meta_type()));
 	assert_type_id!(PhantomData<bool>, TypeIdPrimitive::Bool);
+
+	#[cfg(feature = "float")]
+	assert_type_id!(f32, TypeIdPrimitive::F32);
+	#[cfg(feature = "float")]
+	assert_type_id!(f64, TypeIdPrimitive::F64);
+	assert_type_id!(core::convert::Infallible, TypeIdPrimitive::Never);
+	#[cfg(not(feature = "usize-32"))]
+	assert_type_id!(usize, TypeIdPrimitive::U64);
+	#[cfg(feature = "usize-32")]
+	assert_type_id!(usize, TypeIdPrimitive::U32);
+}
+
+#[test]
+fn transparent_wrappers() {
+	use crate::tm_std::{Arc, Cell, Cow, Rc, RefCell};
+
+	assert_type_id!(Rc<String>, TypeIdPrimitive::Str);
+	assert_type_id!(Arc<[u8]>, TypeIdSlice::new(u8::meta_type()));
+	assert_type_id!(Cow<'static, str>, TypeIdPrimitive::Str);
+	assert_type_id!(Cow<'static, [u8]>, TypeIdSlice::new(u8::meta_type()));
+	assert_type_id!(Cell<u32>, TypeIdPrimitive::U32);
+	assert_type_id!(RefCell<Option<bool>>, <Option<bool>>::type_id());
+	assert_eq!(<Arc<Option<bool>>>::type_def(), <Option<bool>>::type_def());
+	assert_eq!(<Rc<Option<bool>>>::generic_type(), <Option<bool>>::generic_type());
+
+	#[cfg(feature = "std")]
+	{
+		use std::sync::{Mutex, RwLock};
+
+		assert_type_id!(Mutex<Vec<u8>>, <Vec<u8>>::type_id());
+		assert_type_id!(RwLock<u64>, TypeIdPrimitive::U64);
+	}
 }
 
 #[test]
@@ -56,10 +100,114 @@ fn prelude_items() {
 	);
 }
 
+#[test]
+fn numeric_wrappers() {
+	use core::{
+		cmp::Reverse,
+		num::{NonZeroI8, NonZeroU32, NonZeroUsize, Saturating, Wrapping},
+	};
+
+	assert_type_id!(NonZeroU32, TypeIdCustom::new("NonZeroU32", Namespace::prelude(), vec![]));
+	assert_type_id!(NonZeroI8, TypeIdCustom::new("NonZeroI8", Namespace::prelude(), vec![]));
+	assert_eq!(
+		NonZeroU32::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::of::<u32>().non_zero()]).into()
+	);
+	#[cfg(not(feature = "usize-32"))]
+	assert_type_id!(NonZeroUsize, TypeIdCustom::new("NonZeroU64", Namespace::prelude(), vec![]));
+
+	assert_type_id!(Wrapping<u16>, TypeIdPrimitive::U16);
+	assert_type_id!(Saturating<i64>, TypeIdPrimitive::I64);
+	assert_type_id!(Reverse<Option<u8>>, <Option<u8>>::type_id());
+	assert_eq!(<Reverse<Option<u8>>>::type_def(), <Option<u8>>::type_def());
+}
+
+#[test]
+fn ranges_bounds_and_duration() {
+	use core::{
+		ops::{Bound, Range, RangeInclusive},
+		time::Duration,
+	};
+
+	assert_type_id!(
+		Range<u32>,
+		TypeIdCustom::new("Range", Namespace::prelude(), tuple_meta_type!(u32))
+	);
+	let range_def = TypeDef::new(
+		vec!["Idx"],
+		TypeDefStruct::new(vec![NamedField::of::<u32>("start"), NamedField::of::<u32>("end")]),
+	);
+	assert_eq!(<Range<u32>>::type_def(), range_def);
+	assert_eq!(<RangeInclusive<u32>>::type_def(), range_def);
+	assert_eq!(
+		<Bound<u64>>::type_def(),
+		TypeDef::new(
+			vec!["T"],
+			TypeDefEnum::new(vec![
+				EnumVariantTupleStruct::new("Included", vec![UnnamedField::of::<u64>()]).into(),
+				EnumVariantTupleStruct::new("Excluded", vec![UnnamedField::of::<u64>()]).into(),
+				EnumVariantUnit::new("Unbounded").into(),
+			])
+		)
+	);
+	assert_eq!(
+		<Bound<u64>>::generic_type(),
+		Some(<Bound<GenericParameter<0>>>::meta_type())
+	);
+	assert_type_id!(
+		Duration,
+		TypeIdCustom::new("Duration", Namespace::prelude(), vec![])
+	);
+	assert_eq!(
+		Duration::type_def(),
+		TypeDefStruct::new(vec![NamedField::of::<u64>("secs"), NamedField::of::<u32>("nanos")]).into()
+	);
+}
+
+#[test]
+fn collections() {
+	use crate::tm_std::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+
+	assert_type_id!(
+		BTreeMap<String, u32>,
+		TypeIdCustom::new("BTreeMap", Namespace::prelude(), tuple_meta_type!(String, u32))
+	);
+	assert_type_id!(
+		VecDeque<bool>,
+		TypeIdCustom::new("VecDeque", Namespace::prelude(), tuple_meta_type!(bool))
+	);
+	assert_eq!(
+		<BTreeMap<String, u32>>::type_def(),
+		TypeDef::new(
+			vec!["K", "V"],
+			TypeDefStruct::new(vec![NamedField::new("elems", <[(String, u32)]>::meta_type())])
+		)
+	);
+	let elems_of = |ty| TypeDef::new(vec!["T"], TypeDefStruct::new(vec![NamedField::new("elems", ty)]));
+	assert_eq!(<BTreeSet<u8>>::type_def(), elems_of(<[u8]>::meta_type()));
+	assert_eq!(<BinaryHeap<u8>>::type_def(), elems_of(<[u8]>::meta_type()));
+	assert_eq!(<LinkedList<u8>>::type_def(), elems_of(<[u8]>::meta_type()));
+
+	#[cfg(feature = "std")]
+	{
+		use std::collections::{hash_map::RandomState, HashMap, HashSet};
+
+		assert_type_id!(
+			HashMap<String, u32>,
+			TypeIdCustom::new("HashMap", Namespace::prelude(), tuple_meta_type!(String, u32))
+		);
+		assert_eq!(<HashSet<u8, RandomState>>::type_def(), elems_of(<[u8]>::meta_type()));
+		assert_eq!(
+			<HashMap<u8, bool>>::generic_type(),
+			Some(<HashMap<GenericParameter<0>, GenericParameter<1>>>::meta_type())
+		);
+	}
+}
+
 #[test]
 fn tuple_primitives() {
 	// unit
-	assert_type_id!((), TypeIdTuple::new(tuple_meta_type!()));
+	assert_type_id!((), TypeIdPrimitive::Unit);
 
 	// tuple with one element
 	assert_type_id!((bool,), TypeIdTuple::new(tuple_meta_type!(bool)));
@@ -143,3 +291,651 @@ fn struct_with_generics() {
 		TypeDefStruct::new(vec![NamedField::new("data", <Box<MyStruct<bool>>>::meta_type()),]).into(),
 	);
 }
+
+#[test]
+fn owned_registry_roundtrip() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Option<Vec<bool>>>::meta_type());
+	registry.register_type(&<Result<(u8, [i32; 4]), String>>::meta_type());
+
+	let serialized = serde_json::to_string(&registry).unwrap();
+	let owned: OwnedRegistry = serde_json::from_str(&serialized).unwrap();
+	assert_eq!(serde_json::to_string(&owned).unwrap(), serialized);
+	assert_eq!(
+		serde_json::from_str::<OwnedRegistry>(&serde_json::to_string(&owned).unwrap()).unwrap(),
+		owned
+	);
+}
+
+#[test]
+fn owned_registry_resolves_strings() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Option<bool>>::meta_type());
+
+	let owned: OwnedRegistry = serde_json::from_value(serde_json::to_value(&registry).unwrap()).unwrap();
+	let owned_types = serde_json::to_value(owned.owned_types()).unwrap();
+	assert_eq!(
+		owned_types,
+		serde_json::json!([
+			{
+				"id": { "Custom": { "name": "Option", "namespace": { "segments": [] }, "type": [2] } },
+				"def": {
+					"generic_params": { "params": [{ "name": "T" }] },
+					"kind": { "Enum": { "variants": [
+						{ "Unit": { "name": "None", "index": 0 } },
+						{ "TupleStruct": { "name": "Some", "fields": [{ "type": 2 }], "index": 1 } },
+					] } },
+				},
+			},
+			{
+				"id": { "Primitive": "bool" },
+				"def": { "generic_params": { "params": [] }, "kind": "Builtin" },
+			},
+		])
+	);
+}
+
+#[test]
+fn owned_registry_rejects_invalid_symbols() {
+	let invalid_string = serde_json::json!({
+		"string_table": ["Option"],
+		"types": [{
+			"id": { "Custom": { "name": 2, "namespace": { "segments": [] }, "type": [] } },
+			"def": { "generic_params": { "params": [] }, "kind": "Builtin" },
+		}],
+	});
+	assert!(serde_json::from_value::<OwnedRegistry>(invalid_string).is_err());
+
+	let invalid_type = serde_json::json!({
+		"string_table": [],
+		"types": [{
+			"id": { "Slice": { "type": 2 } },
+			"def": { "generic_params": { "params": [] }, "kind": "Builtin" },
+		}],
+	});
+	assert!(serde_json::from_value::<OwnedRegistry>(invalid_type).is_err());
+}
+
+#[test]
+fn owned_registry_resolves_symbols() {
+	let mut registry = Registry::new();
+	let option_symbol = registry.register_type(&<Option<Vec<u8>>>::meta_type());
+	let u8_symbol = registry.register_type(&u8::meta_type());
+	let owned = OwnedRegistry::from(registry);
+
+	let option = owned.resolve(option_symbol).unwrap();
+	let custom = match option.id() {
+		TypeId::Custom(custom) => custom,
+		other => panic!("expected custom type ID but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*custom.name()), Some("Option"));
+	assert!(custom.namespace().segments().is_empty());
+
+	// Walk from `Option<Vec<u8>>` through `Vec<u8>` and `[u8]` to `u8`.
+	let vec = owned.resolve(custom.type_params()[0]).unwrap();
+	let elems = match vec.def().kind() {
+		TypeDefKind::Struct(r#struct) => &r#struct.fields()[0],
+		other => panic!("expected struct definition but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*elems.name()), Some("elems"));
+	let slice = match owned.resolve(*elems.ty()).unwrap().id() {
+		TypeId::Slice(slice) => slice,
+		other => panic!("expected slice type ID but found {:?}", other),
+	};
+	assert_eq!(*slice.type_param(), u8_symbol);
+	assert_eq!(
+		owned.resolve(u8_symbol).unwrap().id(),
+		&TypeId::Primitive(TypeIdPrimitive::U8)
+	);
+
+	let symbols = owned.enumerate().map(|(symbol, _, _)| symbol).collect::<Vec<_>>();
+	assert_eq!(symbols.len(), owned.types().len());
+	for (symbol, id, def) in owned.enumerate() {
+		let type_id_def = owned.resolve(symbol).unwrap();
+		assert_eq!((type_id_def.id(), type_id_def.def()), (id, def));
+	}
+}
+
+#[test]
+fn owned_registry_from_registry_matches_deserialized() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Result<Option<String>, [u16; 2]>>::meta_type());
+	let serialized = serde_json::to_string(&registry).unwrap();
+	let deserialized: OwnedRegistry = serde_json::from_str(&serialized).unwrap();
+	assert_eq!(OwnedRegistry::from(registry), deserialized);
+}
+
+#[test]
+fn merge_registries_deduplicates_strings_and_types() {
+	let mut registry = Registry::new();
+	let u8_symbol = registry.register_type(&u8::meta_type());
+	let option_symbol = registry.register_type(&<Option<u8>>::meta_type());
+
+	let mut other = Registry::new();
+	let other_result_symbol = other.register_type(&<Result<Option<u8>, bool>>::meta_type());
+	let other_option_symbol = other.register_type(&<Option<u8>>::meta_type());
+	let other_u8_symbol = other.register_type(&u8::meta_type());
+	let other_ok_symbol = other.register_string("Ok");
+
+	let mapping = registry.merge(other);
+	assert_eq!(mapping.map_type(other_u8_symbol), Some(u8_symbol));
+	assert_eq!(mapping.map_type(other_option_symbol), Some(option_symbol));
+	assert_eq!(registry.register_type(&<Option<u8>>::meta_type()), option_symbol);
+	let result_symbol = mapping.map_type(other_result_symbol).unwrap();
+	assert_eq!(
+		registry.register_type(&<Result<Option<u8>, bool>>::meta_type()),
+		result_symbol
+	);
+	assert_eq!(
+		mapping.map_string(other_ok_symbol),
+		Some(registry.register_string("Ok"))
+	);
+
+	let mut expected = Registry::new();
+	expected.register_type(&u8::meta_type());
+	expected.register_type(&<Option<u8>>::meta_type());
+	expected.register_type(&<Result<Option<u8>, bool>>::meta_type());
+	let owned = OwnedRegistry::from(registry);
+	let expected = OwnedRegistry::from(expected);
+	let string_table = |registry: &OwnedRegistry| {
+		let mut strings = serde_json::from_value::<Vec<String>>(
+			serde_json::to_value(registry).unwrap()["string_table"].clone()
+		).unwrap();
+		strings.sort();
+		strings
+	};
+	assert_eq!(string_table(&owned), string_table(&expected));
+	assert_eq!(owned.types().len(), expected.types().len());
+
+	let result = match owned.resolve(result_symbol).unwrap().id() {
+		TypeId::Custom(custom) => custom,
+		other => panic!("expected custom type ID but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*result.name()), Some("Result"));
+	assert_eq!(result.type_params()[0], option_symbol);
+}
+
+#[test]
+fn merge_registries_deduplicates_identical_definitions() {
+	fn register_my_struct(registry: &mut Registry) -> interner::UntrackedSymbol<core::any::TypeId> {
+		#[allow(unused)]
+		struct MyStruct {
+			data: u8,
+		}
+
+		impl HasTypeId for MyStruct {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("MyStruct", Namespace::from_str(module_path!()).unwrap(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for MyStruct {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![NamedField::new("data", u8::meta_type())]).into()
+			}
+		}
+
+		registry.register_type(&MyStruct::meta_type())
+	}
+
+	// A distinct Rust type that is described by the same metadata.
+	#[allow(unused)]
+	struct MyStruct {
+		data: u8,
+	}
+
+	impl HasTypeId for MyStruct {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("MyStruct", Namespace::from_str(module_path!()).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for MyStruct {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::new("data", u8::meta_type())]).into()
+		}
+	}
+
+	let mut registry = Registry::new();
+	let symbol = register_my_struct(&mut registry);
+	let mut other = Registry::new();
+	let other_symbol = other.register_type(&MyStruct::meta_type());
+
+	let mapping = registry.merge(other);
+	assert_eq!(mapping.map_type(other_symbol), Some(symbol));
+	assert_eq!(registry.register_type(&MyStruct::meta_type()), symbol);
+	assert_eq!(OwnedRegistry::from(registry).types().len(), 2);
+}
+
+#[test]
+fn merge_registries_with_recursive_types() {
+	#[allow(unused)]
+	struct Node {
+		next: Option<Box<Node>>,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::from_str(module_path!()).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![NamedField::new("next", <Option<Box<Node>>>::meta_type())]).into()
+		}
+	}
+
+	let mut registry = Registry::new();
+	registry.register_type(&<(bool, String)>::meta_type());
+	let mut other = Registry::new();
+	let other_node_symbol = other.register_type(&Node::meta_type());
+
+	let mapping = registry.merge(other);
+	let node_symbol = mapping.map_type(other_node_symbol).unwrap();
+	assert_eq!(registry.register_type(&Node::meta_type()), node_symbol);
+
+	let owned = OwnedRegistry::from(registry);
+	let next = match owned.resolve(node_symbol).unwrap().def().kind() {
+		TypeDefKind::Struct(r#struct) => &r#struct.fields()[0],
+		other => panic!("expected struct definition but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*next.name()), Some("next"));
+	let option = match owned.resolve(*next.ty()).unwrap().id() {
+		TypeId::Custom(custom) => custom,
+		other => panic!("expected custom type ID but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*option.name()), Some("Option"));
+	assert_eq!(option.type_params(), &[node_symbol]);
+}
+
+#[test]
+fn merge_registries_deduplicates_identical_recursive_types() {
+	fn register_tree(registry: &mut Registry) -> interner::UntrackedSymbol<core::any::TypeId> {
+		#[allow(unused)]
+		struct Tree {
+			parent: Option<Box<Tree>>,
+			children: Vec<Tree>,
+		}
+
+		impl HasTypeId for Tree {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Tree", Namespace::from_str(module_path!()).unwrap(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Tree {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![
+					NamedField::new("parent", <Option<Box<Tree>>>::meta_type()),
+					NamedField::new("children", <Vec<Tree>>::meta_type()),
+				])
+				.into()
+			}
+		}
+
+		registry.register_type(&<(Tree, Option<Box<Tree>>)>::meta_type())
+	}
+
+	// A distinct Rust type that is described by the same metadata.
+	#[allow(unused)]
+	struct Tree {
+		parent: Option<Box<Tree>>,
+		children: Vec<Tree>,
+	}
+
+	impl HasTypeId for Tree {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Tree", Namespace::from_str(module_path!()).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Tree {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::new("parent", <Option<Box<Tree>>>::meta_type()),
+				NamedField::new("children", <Vec<Tree>>::meta_type()),
+			])
+			.into()
+		}
+	}
+
+	let mut registry = Registry::new();
+	let symbol = register_tree(&mut registry);
+	let mut expected = Registry::new();
+	register_tree(&mut expected);
+	let mut other = Registry::new();
+	let other_symbol = other.register_type(&<(Tree, Option<Box<Tree>>)>::meta_type());
+
+	let mapping = registry.merge(other);
+	assert_eq!(mapping.map_type(other_symbol), Some(symbol));
+	assert_eq!(
+		registry.register_type(&<(Tree, Option<Box<Tree>>)>::meta_type()),
+		symbol
+	);
+	assert_eq!(OwnedRegistry::from(registry), OwnedRegistry::from(expected));
+}
+
+#[test]
+fn retain_reachable_types() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Result<u16, String>>::meta_type());
+	let option_symbol = registry.register_type(&<Option<(u8, bool)>>::meta_type());
+	registry.register_type(&<[i32; 4]>::meta_type());
+	let bool_symbol = registry.register_type(&bool::meta_type());
+	let name_symbol = registry.register_string("Option");
+
+	let mapping = registry.retain(vec![option_symbol]);
+	let new_option_symbol = mapping.map_type(option_symbol).unwrap();
+	let new_bool_symbol = mapping.map_type(bool_symbol).unwrap();
+	assert_eq!(
+		registry.register_type(&<Option<(u8, bool)>>::meta_type()),
+		new_option_symbol
+	);
+	assert_eq!(registry.register_type(&bool::meta_type()), new_bool_symbol);
+	assert_eq!(
+		mapping.map_string(name_symbol),
+		Some(registry.register_string("Option"))
+	);
+	assert_eq!(mapping.types().count(), 4);
+	assert_eq!(mapping.strings().count(), 4);
+
+	let mut expected = Registry::new();
+	expected.register_type(&<Option<(u8, bool)>>::meta_type());
+	let owned = OwnedRegistry::from(registry);
+	let expected = OwnedRegistry::from(expected);
+	let string_table = |registry: &OwnedRegistry| {
+		let mut strings = serde_json::from_value::<Vec<String>>(
+			serde_json::to_value(registry).unwrap()["string_table"].clone()
+		).unwrap();
+		strings.sort();
+		strings
+	};
+	assert_eq!(string_table(&owned), string_table(&expected));
+	assert_eq!(owned.types().len(), expected.types().len());
+	let option = match owned.resolve(new_option_symbol).unwrap().id() {
+		TypeId::Custom(custom) => custom,
+		other => panic!("expected custom type ID but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(*option.name()), Some("Option"));
+	match owned.resolve(option.type_params()[0]).unwrap().id() {
+		TypeId::Tuple(tuple) => assert_eq!(tuple.type_params[1], new_bool_symbol),
+		other => panic!("expected tuple type ID but found {:?}", other),
+	}
+}
+
+#[test]
+fn retain_nothing() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Vec<Option<u8>>>::meta_type());
+	let mapping = registry.retain(vec![]);
+	assert_eq!(mapping, SymbolMapping::default());
+	assert_eq!(
+		serde_json::to_value(&registry).unwrap(),
+		serde_json::to_value(Registry::new()).unwrap()
+	);
+}
+
+#[test]
+fn canonicalize_is_independent_of_registration_order() {
+	let mut registry = Registry::new();
+	registry.register_type(&<Option<(u8, bool)>>::meta_type());
+	registry.register_string("unused");
+	registry.register_type(&<Result<[u16; 2], Vec<u8>>>::meta_type());
+
+	let mut other = Registry::new();
+	let vec_symbol = other.register_type(&<Vec<u8>>::meta_type());
+	other.register_type(&<Result<[u16; 2], Vec<u8>>>::meta_type());
+	other.register_string("unused");
+	other.register_type(&<Option<(u8, bool)>>::meta_type());
+	assert_ne!(
+		serde_json::to_value(&registry).unwrap(),
+		serde_json::to_value(&other).unwrap()
+	);
+
+	registry.canonicalize();
+	let mapping = other.canonicalize();
+	assert_eq!(
+		serde_json::to_value(&registry).unwrap(),
+		serde_json::to_value(&other).unwrap()
+	);
+	assert_eq!(
+		other.register_type(&<Vec<u8>>::meta_type()),
+		mapping.map_type(vec_symbol).unwrap()
+	);
+	assert_eq!(
+		serde_json::to_value(&registry).unwrap()["string_table"],
+		serde_json::json!(["E", "Err", "None", "Ok", "Option", "Result", "Some", "T", "Vec", "elems", "unused"])
+	);
+
+	// Canonicalization is idempotent.
+	let mapping = registry.canonicalize();
+	assert!(mapping.types().all(|(old, new)| old == new));
+	assert!(mapping.strings().all(|(old, new)| old == new));
+}
+
+#[test]
+fn owned_registry_type_paths() {
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&<Result<Option<(u8,)>, ([bool; 4], Vec<String>)>>::meta_type());
+	let owned = OwnedRegistry::from(registry);
+	assert_eq!(
+		owned.type_path(symbol).as_deref(),
+		Some("Result<Option<(u8,)>, ([bool; 4], Vec<str>)>")
+	);
+}
+
+#[test]
+fn shared_generics_store_generic_definitions_once() {
+	let mut registry = Registry::with_shared_generics();
+	let vec_u8 = registry.register_type(&<Vec<u8>>::meta_type());
+	let vec_bool = registry.register_type(&<Vec<bool>>::meta_type());
+	registry.register_type(&<Option<Vec<u8>>>::meta_type());
+
+	let serialized = serde_json::to_value(&registry).unwrap();
+	let defined = serialized["types"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.filter(|ty| ty.get("def").is_some() && ty["id"].get("Custom").is_some())
+		.count();
+	assert_eq!(defined, 2);
+
+	let owned: OwnedRegistry = serde_json::from_value(serialized.clone()).unwrap();
+	assert_eq!(serde_json::to_value(&owned).unwrap(), serialized);
+	assert_eq!(owned, OwnedRegistry::from(registry));
+
+	let generic = *owned.resolve(vec_bool).unwrap().generic().unwrap();
+	assert_eq!(owned.resolve(vec_u8).unwrap().generic(), Some(&generic));
+	assert_eq!(owned.type_path(generic).as_deref(), Some("Vec<$0>"));
+	assert!(owned.is_parametric(generic));
+	assert!(!owned.is_parametric(vec_bool));
+	let elems = match owned.resolve(vec_bool).unwrap().def().kind() {
+		TypeDefKind::Struct(r#struct) => *r#struct.fields()[0].ty(),
+		other => panic!("expected struct definition but found {:?}", other),
+	};
+	assert_eq!(owned.type_path(elems).as_deref(), Some("[bool]"));
+}
+
+#[test]
+fn shared_generics_of_recursive_types() {
+	#[allow(unused)]
+	struct List<T> {
+		head: T,
+		tail: Option<Box<List<T>>>,
+	}
+
+	impl<T> HasTypeId for List<T>
+	where
+		T: Metadata + 'static,
+	{
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("List", Namespace::new(vec!["app"]).unwrap(), tuple_meta_type!(T)).into()
+		}
+	}
+
+	impl<T> HasTypeDef for List<T>
+	where
+		T: Metadata + 'static,
+	{
+		fn type_def() -> TypeDef {
+			TypeDef::new(
+				vec!["T"],
+				TypeDefStruct::new(vec![
+					NamedField::of::<T>("head"),
+					NamedField::of::<Option<Box<List<T>>>>("tail"),
+				]),
+			)
+		}
+
+		fn generic_type() -> Option<MetaType> {
+			Some(MetaType::new::<List<GenericParameter<0>>>())
+		}
+	}
+
+	let mut registry = Registry::with_shared_generics();
+	let list = registry.register_type(&<List<u8>>::meta_type());
+	let serialized = serde_json::to_string(&registry).unwrap();
+	let owned: OwnedRegistry = serde_json::from_str(&serialized).unwrap();
+	assert_eq!(serde_json::to_string(&owned).unwrap(), serialized);
+
+	let fields = match owned.resolve(list).unwrap().def().kind() {
+		TypeDefKind::Struct(r#struct) => r#struct.fields().to_vec(),
+		other => panic!("expected struct definition but found {:?}", other),
+	};
+	let paths = fields
+		.iter()
+		.map(|field| owned.type_path(*field.ty()).unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(paths, vec!["u8", "Option<app::List<u8>>"]);
+}
+
+#[test]
+fn owned_registry_rejects_invalid_generics() {
+	let rejection = |value: serde_json::Value| serde_json::from_value::<OwnedRegistry>(value).unwrap_err().to_string();
+
+	let missing_def = serde_json::json!({
+		"string_table": ["Option"],
+		"types": [{
+			"id": { "Custom": { "name": 1, "namespace": { "segments": [] }, "type": [] } },
+		}],
+	});
+	assert_eq!(rejection(missing_def), "type must have either a definition or a generic type");
+
+	let parameter_out_of_bounds = serde_json::json!({
+		"string_table": ["Wrapper", "value"],
+		"types": [
+			{
+				"id": { "Custom": { "name": 1, "namespace": { "segments": [] }, "type": [] } },
+				"generic": 2,
+			},
+			{
+				"id": { "Custom": { "name": 1, "namespace": { "segments": [] }, "type": [3] } },
+				"def": { "generic_params": { "params": [] }, "kind": { "Struct": { "fields": [
+					{ "name": 2, "type": 3 },
+				] } } },
+			},
+			{
+				"id": { "Parameter": 0 },
+				"def": { "generic_params": { "params": [] }, "kind": "Builtin" },
+			},
+		],
+	});
+	assert_eq!(
+		rejection(parameter_out_of_bounds),
+		"generic parameter out of bounds of the type parameters"
+	);
+}
+
+#[test]
+fn docs_are_interned() {
+	struct Documented;
+
+	impl HasTypeId for Documented {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Documented", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Documented {
+		fn type_def() -> TypeDef {
+			TypeDef::from(TypeDefStruct::new(vec![
+				NamedField::of::<u8>("value").with_docs(&["The value."])
+			]))
+			.with_docs(&["A documented type.", "The value."])
+		}
+	}
+
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&Documented::meta_type());
+	let serialized = serde_json::to_value(&registry).unwrap();
+	assert_eq!(
+		serialized["string_table"],
+		serde_json::json!(["Documented", "app", "value", "The value.", "A documented type."])
+	);
+	assert_eq!(serialized["types"][1]["def"].get("docs"), None);
+
+	let owned: OwnedRegistry = serde_json::from_value(serialized).unwrap();
+	let def = owned.resolve(symbol).unwrap().def();
+	let docs = def
+		.docs()
+		.iter()
+		.map(|doc| owned.resolve_string(*doc).unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(docs, vec!["A documented type.", "The value."]);
+	let field = match def.kind() {
+		TypeDefKind::Struct(r#struct) => &r#struct.fields()[0],
+		other => panic!("expected struct definition but found {:?}", other),
+	};
+	assert_eq!(owned.resolve_string(field.docs()[0]), Some("The value."));
+}
+
+#[test]
+fn enum_tagging_and_flattened_fields_round_trip() {
+	struct Tagged;
+
+	impl HasTypeId for Tagged {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Tagged", Namespace::prelude(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Tagged {
+		fn type_def() -> TypeDef {
+			TypeDefEnum::new(vec![EnumVariantStruct::new(
+				"Point",
+				vec![NamedField::of::<(u8, u8)>("coords").flattened()],
+			)
+			.into()])
+			.with_tagging(EnumTagging::Adjacent {
+				tag: "kind",
+				content: "data",
+			})
+			.into()
+		}
+	}
+
+	let mut registry = Registry::new();
+	let symbol = registry.register_type(&Tagged::meta_type());
+	let serialized = serde_json::to_value(&registry).unwrap();
+	let def = &serialized["types"][0]["def"]["kind"]["Enum"];
+	assert_eq!(def["tagging"], serde_json::json!({ "Adjacent": { "tag": 4, "content": 5 } }));
+	assert_eq!(def["variants"][0]["Struct"]["fields"][0]["flatten"], serde_json::json!(true));
+
+	let owned: OwnedRegistry = serde_json::from_value(serialized).unwrap();
+	let r#enum = match owned.resolve(symbol).unwrap().def().kind() {
+		TypeDefKind::Enum(r#enum) => r#enum,
+		other => panic!("expected enum definition but found {:?}", other),
+	};
+	match r#enum.tagging() {
+		EnumTagging::Adjacent { tag, content } => {
+			assert_eq!(owned.resolve_string(*tag), Some("kind"));
+			assert_eq!(owned.resolve_string(*content), Some("data"));
+		}
+		other => panic!("expected adjacent tagging but found {:?}", other),
+	}
+	match &r#enum.variants()[0] {
+		EnumVariant::Struct(r#struct) => assert!(r#struct.fields()[0].is_flattened()),
+		other => panic!("expected struct variant but found {:?}", other),
+	}
+}
@@ -26,22 +26,26 @@ mod core {
 
 #[rustfmt::skip]
 pub use self::core::{
-	i8, i16, i32, i64, i128,
-	u8, u16, u32, u64, u128,
-
 	marker::PhantomData,
-	num::NonZeroU32,
+	cmp::Reverse,
+	num::{
+		NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32,
+		NonZeroU64, NonZeroU8, NonZeroUsize, Saturating, Wrapping,
+	},
 	option::Option,
 	result::Result,
 
 	any::{TypeId as AnyTypeId},
 
+	cell::{Cell, RefCell},
+
 	clone::{Clone},
 	cmp::{Eq, PartialEq, Ordering},
-	convert::{From, Into},
+	convert::{From, Infallible, Into, TryFrom},
 	fmt::{Debug, Error as FmtError, Formatter},
 	hash::{Hash, Hasher},
-	iter,
+	ops::{Bound, Range, RangeInclusive},
+	time::Duration,
 };
 
 mod alloc {
@@ -54,8 +58,20 @@ mod alloc {
 
 #[rustfmt::skip]
 pub use self::alloc::{
+	borrow::{Cow, ToOwned},
 	boxed::Box,
+	format,
 	collections::btree_map::{BTreeMap, Entry},
-	string::{String, ToString},
+	collections::btree_set::BTreeSet,
+	collections::{BinaryHeap, LinkedList, VecDeque},
+	rc::Rc,
+	string::String,
+	sync::Arc,
 	vec, vec::Vec,
 };
+
+#[cfg(feature = "std")]
+pub use std::{
+	collections::{HashMap, HashSet},
+	sync::{Mutex, RwLock},
+};
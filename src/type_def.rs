@@ -17,11 +17,11 @@
 use crate::tm_std::*;
 
 use crate::{
-	form::{CompactForm, Form, MetaForm},
-	IntoCompact, MetaType, Metadata, Registry,
+	form::{CompactForm, Form, FormMapper, MapForm, MetaForm},
+	HasTypeId, IntoCompact, MetaType, Metadata, Registry, TypeId, TypeIdPrimitive,
 };
 use derive_more::From;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// Types implementing this trait can communicate their type structure.
 ///
@@ -30,10 +30,24 @@ use serde::Serialize;
 /// implementation must register these contained types' metadata.
 pub trait HasTypeDef {
 	fn type_def() -> TypeDef;
+
+	/// Returns the generic type of which `Self` is an instantiation.
+	///
+	/// The generic type is `Self` with all of its type parameters replaced by
+	/// `GenericParameter` markers, e.g. `Option<GenericParameter<0>>` for `Option<u32>`.
+	/// Its type definition is shared by all instantiations of the generic type.
+	///
+	/// Returns `None` for types that are not generic.
+	fn generic_type() -> Option<MetaType> {
+		None
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDef<F: Form = MetaForm> {
 	/// Stores count and names of all generic parameters.
 	///
@@ -42,6 +56,9 @@ pub struct TypeDef<F: Form = MetaForm> {
 	generic_params: GenericParams<F>,
 	/// The underlying structure of the type definition.
 	kind: TypeDefKind<F>,
+	/// The documentation of the type.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for TypeDef {
@@ -51,6 +68,30 @@ impl IntoCompact for TypeDef {
 		TypeDef {
 			generic_params: self.generic_params.into_compact(registry),
 			kind: self.kind.into_compact(registry),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for TypeDef<S> {
+	type Output = TypeDef<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDef {
+			generic_params: self.generic_params.map_form(mapper),
+			kind: self.kind.map_form(mapper),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
@@ -64,10 +105,11 @@ impl TypeDef {
 		Self {
 			generic_params: generic_params
 				.into_iter()
-				.map(|name| GenericArg::from(name))
+				.map(GenericArg::from)
 				.collect::<Vec<_>>()
 				.into(),
 			kind: kind.into(),
+			docs: vec![],
 		}
 	}
 }
@@ -80,6 +122,7 @@ where
 		Self {
 			generic_params: GenericParams::empty(),
 			kind: kind.into(),
+			docs: vec![],
 		}
 	}
 }
@@ -89,16 +132,39 @@ impl TypeDef {
 		Self {
 			generic_params: GenericParams::empty(),
 			kind: TypeDefKind::Builtin,
+			docs: vec![],
 		}
 	}
 
-	pub fn kind(&self) -> &TypeDefKind {
+	/// Sets the documentation of the type.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
+}
+
+impl<F: Form> TypeDef<F> {
+	/// Returns the generic parameters of the type definition.
+	pub fn generic_params(&self) -> &GenericParams<F> {
+		&self.generic_params
+	}
+
+	/// Returns the underlying structure of the type definition.
+	pub fn kind(&self) -> &TypeDefKind<F> {
 		&self.kind
 	}
+
+	/// Returns the documentation of the type.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, From)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, From)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct GenericParams<F: Form = MetaForm> {
 	params: Vec<GenericArg<F>>,
 }
@@ -117,13 +183,38 @@ impl IntoCompact for GenericParams {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for GenericParams<S> {
+	type Output = GenericParams<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		GenericParams {
+			params: self
+				.params
+				.into_iter()
+				.map(|param| param.map_form(mapper))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> GenericParams<F> {
+	/// Returns the generic parameters in the order of their declaration.
+	pub fn params(&self) -> &[GenericArg<F>] {
+		&self.params
+	}
+}
+
 impl GenericParams {
 	pub fn empty() -> Self {
 		Self { params: vec![] }
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "F::String: DeserializeOwned"))]
 pub struct GenericArg<F: Form = MetaForm> {
 	name: F::String,
 }
@@ -138,14 +229,37 @@ impl IntoCompact for GenericArg {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for GenericArg<S> {
+	type Output = GenericArg<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		GenericArg {
+			name: mapper.map_string(self.name),
+		}
+	}
+}
+
+impl<F: Form> GenericArg<F> {
+	/// Returns the name of the generic parameter.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+}
+
 impl From<<MetaForm as Form>::String> for GenericArg {
 	fn from(name: <MetaForm as Form>::String) -> Self {
 		Self { name }
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, From)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, From)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub enum TypeDefKind<F: Form = MetaForm> {
 	Builtin,
 	Struct(TypeDefStruct<F>),
@@ -170,8 +284,34 @@ impl IntoCompact for TypeDefKind {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefKind<S> {
+	type Output = TypeDefKind<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		match self {
+			TypeDefKind::Builtin => TypeDefKind::Builtin,
+			TypeDefKind::Struct(r#struct) => r#struct.map_form(mapper).into(),
+			TypeDefKind::TupleStruct(tuple_struct) => tuple_struct.map_form(mapper).into(),
+			TypeDefKind::ClikeEnum(clike_enum) => clike_enum.map_form(mapper).into(),
+			TypeDefKind::Enum(r#enum) => r#enum.map_form(mapper).into(),
+			TypeDefKind::Union(union) => union.map_form(mapper).into(),
+		}
+	}
+}
+
+/// A struct with named fields.
+///
+/// Collections of the prelude are described like `Vec`, as a struct with a single `elems` field
+/// of the slice of their elements: sets such as `BTreeSet<T>` by `[T]` and maps such as
+/// `BTreeMap<K, V>` by `[(K, V)]` of their entries. They are told apart by their type id only.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDefStruct<F: Form = MetaForm> {
 	fields: Vec<NamedField<F>>,
 }
@@ -190,6 +330,30 @@ impl IntoCompact for TypeDefStruct {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefStruct<S> {
+	type Output = TypeDefStruct<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDefStruct {
+			fields: self
+				.fields
+				.into_iter()
+				.map(|field| field.map_form(mapper))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> TypeDefStruct<F> {
+	/// Returns the fields of the struct.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+}
+
 impl TypeDefStruct {
 	pub fn new<F>(fields: F) -> Self
 	where
@@ -201,12 +365,23 @@ impl TypeDefStruct {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct NamedField<F: Form = MetaForm> {
 	name: F::String,
 	#[serde(rename = "type")]
 	ty: F::TypeId,
+	/// Whether the fields of the field type are inlined into the enclosing fields.
+	///
+	/// This corresponds to `#[serde(flatten)]`.
+	#[serde(default, skip_serializing_if = "is_false")]
+	flatten: bool,
+	/// The documentation of the field.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for NamedField {
@@ -216,13 +391,66 @@ impl IntoCompact for NamedField {
 		NamedField {
 			name: registry.register_string(self.name),
 			ty: registry.register_type(&self.ty),
+			flatten: self.flatten,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for NamedField<S> {
+	type Output = NamedField<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		NamedField {
+			name: mapper.map_string(self.name),
+			ty: mapper.map_type_id(self.ty),
+			flatten: self.flatten,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> NamedField<F> {
+	/// Returns the name of the field.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns `true` if the fields of the field type are inlined into the enclosing fields.
+	pub fn is_flattened(&self) -> bool {
+		self.flatten
+	}
+
+	/// Returns the type of the field.
+	pub fn ty(&self) -> &F::TypeId {
+		&self.ty
+	}
+
+	/// Returns the documentation of the field.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl NamedField {
 	pub fn new(name: <MetaForm as Form>::String, ty: MetaType) -> Self {
-		Self { name, ty }
+		Self {
+			name,
+			ty,
+			flatten: false,
+			docs: vec![],
+		}
 	}
 
 	pub fn of<T>(name: <MetaForm as Form>::String) -> Self
@@ -231,10 +459,29 @@ impl NamedField {
 	{
 		Self::new(name, MetaType::new::<T>())
 	}
+
+	/// Sets the documentation of the field.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
+
+	/// Inlines the fields of the field type into the enclosing fields.
+	pub fn flattened(mut self) -> Self {
+		self.flatten = true;
+		self
+	}
+}
+
+fn is_false(value: &bool) -> bool {
+	!*value
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDefTupleStruct<F: Form = MetaForm> {
 	fields: Vec<UnnamedField<F>>,
 }
@@ -253,6 +500,30 @@ impl IntoCompact for TypeDefTupleStruct {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefTupleStruct<S> {
+	type Output = TypeDefTupleStruct<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDefTupleStruct {
+			fields: self
+				.fields
+				.into_iter()
+				.map(|field| field.map_form(mapper))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> TypeDefTupleStruct<F> {
+	/// Returns the fields of the tuple struct.
+	pub fn fields(&self) -> &[UnnamedField<F>] {
+		&self.fields
+	}
+}
+
 impl TypeDefTupleStruct {
 	pub fn new<F>(fields: F) -> Self
 	where
@@ -268,11 +539,22 @@ impl TypeDefTupleStruct {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct UnnamedField<F: Form = MetaForm> {
 	#[serde(rename = "type")]
 	ty: F::TypeId,
+	/// Whether the integer value of the field is never zero.
+	///
+	/// This is the invariant of the `NonZero*` integers.
+	#[serde(default, skip_serializing_if = "is_false")]
+	non_zero: bool,
+	/// The documentation of the field.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for UnnamedField {
@@ -281,13 +563,59 @@ impl IntoCompact for UnnamedField {
 	fn into_compact(self, registry: &mut Registry) -> Self::Output {
 		UnnamedField {
 			ty: registry.register_type(&self.ty),
+			non_zero: self.non_zero,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for UnnamedField<S> {
+	type Output = UnnamedField<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		UnnamedField {
+			ty: mapper.map_type_id(self.ty),
+			non_zero: self.non_zero,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<F: Form> UnnamedField<F> {
+	/// Returns the type of the field.
+	pub fn ty(&self) -> &F::TypeId {
+		&self.ty
+	}
+
+	/// Returns `true` if the integer value of the field is never zero.
+	pub fn is_non_zero(&self) -> bool {
+		self.non_zero
+	}
+
+	/// Returns the documentation of the field.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl UnnamedField {
 	pub fn new(meta_type: MetaType) -> Self {
-		Self { ty: meta_type }
+		Self {
+			ty: meta_type,
+			non_zero: false,
+			docs: vec![],
+		}
 	}
 
 	pub fn of<T>() -> Self
@@ -296,12 +624,32 @@ impl UnnamedField {
 	{
 		Self::new(MetaType::new::<T>())
 	}
+
+	/// Marks the integer value of the field as never zero.
+	pub fn non_zero(mut self) -> Self {
+		self.non_zero = true;
+		self
+	}
+
+	/// Sets the documentation of the field.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDefClikeEnum<F: Form = MetaForm> {
 	variants: Vec<ClikeEnumVariant<F>>,
+	/// The integer type of the discriminants as given by `#[repr(..)]`.
+	///
+	/// Discriminants are encoded at the width of this type, or as a single `u8` if there is none.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	repr: Option<TypeIdPrimitive>,
 }
 
 impl IntoCompact for TypeDefClikeEnum {
@@ -314,10 +662,57 @@ impl IntoCompact for TypeDefClikeEnum {
 				.into_iter()
 				.map(|variant| variant.into_compact(registry))
 				.collect::<Vec<_>>(),
+			repr: self.repr,
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefClikeEnum<S> {
+	type Output = TypeDefClikeEnum<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDefClikeEnum {
+			variants: self
+				.variants
+				.into_iter()
+				.map(|variant| variant.map_form(mapper))
+				.collect::<Vec<_>>(),
+			repr: self.repr,
 		}
 	}
 }
 
+impl<F: Form> TypeDefClikeEnum<F> {
+	/// Returns the variants of the C-like enum.
+	pub fn variants(&self) -> &[ClikeEnumVariant<F>] {
+		&self.variants
+	}
+
+	/// Returns the integer type of the discriminants if the enum has a `#[repr(..)]`.
+	pub fn repr(&self) -> Option<&TypeIdPrimitive> {
+		self.repr.as_ref()
+	}
+
+	/// Returns the number of bytes and the signedness of the encoded discriminants.
+	pub(crate) fn discriminant_layout(&self) -> (usize, bool) {
+		self.repr.as_ref().and_then(integer_layout).unwrap_or((1, false))
+	}
+
+	/// Returns the discriminant encoded by the given little endian bytes.
+	///
+	/// The bytes are sign or zero extended according to the repr of the enum.
+	pub(crate) fn discriminant_from_le_bytes(&self, bytes: &[u8]) -> i128 {
+		let (_, signed) = self.discriminant_layout();
+		let negative = signed && matches!(bytes.last(), Some(byte) if byte & 0x80 != 0);
+		let mut extended = if negative { [0xff; 16] } else { [0; 16] };
+		extended[..bytes.len()].copy_from_slice(bytes);
+		i128::from_le_bytes(extended)
+	}
+}
+
 impl TypeDefClikeEnum {
 	pub fn new<V>(variants: V) -> Self
 	where
@@ -325,14 +720,56 @@ impl TypeDefClikeEnum {
 	{
 		Self {
 			variants: variants.into_iter().collect(),
+			repr: None,
+		}
+	}
+
+	/// Sets the integer type of the discriminants as given by `#[repr(R)]`.
+	///
+	/// # Panics
+	///
+	/// If `R` is not a primitive integer type.
+	pub fn with_repr<R>(mut self) -> Self
+	where
+		R: HasTypeId,
+	{
+		match R::type_id() {
+			TypeId::Primitive(repr) if integer_layout(&repr).is_some() => self.repr = Some(repr),
+			_ => panic!("the repr of a C-like enum must be a primitive integer type"),
 		}
+		self
+	}
+}
+
+/// Returns the number of bytes and the signedness of a primitive integer type.
+fn integer_layout(primitive: &TypeIdPrimitive) -> Option<(usize, bool)> {
+	match primitive {
+		TypeIdPrimitive::U8 => Some((1, false)),
+		TypeIdPrimitive::U16 => Some((2, false)),
+		TypeIdPrimitive::U32 => Some((4, false)),
+		TypeIdPrimitive::U64 => Some((8, false)),
+		TypeIdPrimitive::U128 => Some((16, false)),
+		TypeIdPrimitive::I8 => Some((1, true)),
+		TypeIdPrimitive::I16 => Some((2, true)),
+		TypeIdPrimitive::I32 => Some((4, true)),
+		TypeIdPrimitive::I64 => Some((8, true)),
+		TypeIdPrimitive::I128 => Some((16, true)),
+		_ => None,
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "F::String: DeserializeOwned"))]
 pub struct ClikeEnumVariant<F: Form = MetaForm> {
 	name: F::String,
-	discriminant: u64,
+	/// The discriminant of the variant as evaluated by `Variant as i128`.
+	///
+	/// Together with the repr of the enum this is lossless: discriminants of a
+	/// `#[repr(u128)]` enum above `i128::MAX` are stored by their two's complement.
+	discriminant: i128,
+	/// The documentation of the variant.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for ClikeEnumVariant {
@@ -342,26 +779,80 @@ impl IntoCompact for ClikeEnumVariant {
 		ClikeEnumVariant {
 			name: registry.register_string(self.name),
 			discriminant: self.discriminant,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for ClikeEnumVariant<S> {
+	type Output = ClikeEnumVariant<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		ClikeEnumVariant {
+			name: mapper.map_string(self.name),
+			discriminant: self.discriminant,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<F: Form> ClikeEnumVariant<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the discriminant of the variant as evaluated by `Variant as i128`.
+	pub fn discriminant(&self) -> i128 {
+		self.discriminant
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl ClikeEnumVariant {
 	pub fn new<D>(name: <MetaForm as Form>::String, discriminant: D) -> Self
 	where
-		D: Into<u64>,
+		D: Into<i128>,
 	{
 		Self {
 			name,
 			discriminant: discriminant.into(),
+			docs: vec![],
 		}
 	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDefEnum<F: Form = MetaForm> {
 	variants: Vec<EnumVariant<F>>,
+	/// How the variants are tagged in the serde data model.
+	#[serde(default, skip_serializing_if = "EnumTagging::is_external")]
+	tagging: EnumTagging<F>,
 }
 
 impl IntoCompact for TypeDefEnum {
@@ -374,23 +865,155 @@ impl IntoCompact for TypeDefEnum {
 				.into_iter()
 				.map(|variant| variant.into_compact(registry))
 				.collect::<Vec<_>>(),
+			tagging: self.tagging.into_compact(registry),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefEnum<S> {
+	type Output = TypeDefEnum<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDefEnum {
+			variants: self
+				.variants
+				.into_iter()
+				.map(|variant| variant.map_form(mapper))
+				.collect::<Vec<_>>(),
+			tagging: self.tagging.map_form(mapper),
 		}
 	}
 }
 
+impl<F: Form> TypeDefEnum<F> {
+	/// Returns the variants of the enum.
+	pub fn variants(&self) -> &[EnumVariant<F>] {
+		&self.variants
+	}
+
+	/// Returns how the variants are tagged in the serde data model.
+	pub fn tagging(&self) -> &EnumTagging<F> {
+		&self.tagging
+	}
+
+	/// Returns the variants with the indices by which they are encoded.
+	///
+	/// Variants without a recorded index are encoded by their position.
+	pub fn indexed_variants(&self) -> impl Iterator<Item = (usize, &EnumVariant<F>)> {
+		self.variants.iter().enumerate().map(|(position, variant)| {
+			let index = variant.index().map_or(position, usize::from);
+			(index, variant)
+		})
+	}
+}
+
 impl TypeDefEnum {
+	/// Creates an enum of the given variants.
+	///
+	/// Variants without an explicit index are indexed by their position.
 	pub fn new<V>(variants: V) -> Self
 	where
 		V: IntoIterator<Item = EnumVariant>,
 	{
 		Self {
-			variants: variants.into_iter().collect(),
+			variants: variants
+				.into_iter()
+				.enumerate()
+				.map(|(position, variant)| match (variant.index(), u8::try_from(position)) {
+					(None, Ok(index)) => variant.with_index(index),
+					_ => variant,
+				})
+				.collect(),
+			tagging: EnumTagging::External,
+		}
+	}
+
+	/// Sets how the variants are tagged in the serde data model.
+	pub fn with_tagging(mut self, tagging: EnumTagging) -> Self {
+		self.tagging = tagging;
+		self
+	}
+}
+
+/// How the variants of an enum are tagged in the serde data model.
+///
+/// The examples show the JSON representation of a variant `V` with a field `f`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "F::String: DeserializeOwned"))]
+pub enum EnumTagging<F: Form = MetaForm> {
+	/// `{"V": {"f": ...}}`, the default.
+	#[default]
+	External,
+	/// `{"tag": "V", "f": ...}`, see `#[serde(tag = "tag")]`.
+	Internal {
+		/// The name of the field that holds the variant name.
+		tag: F::String,
+	},
+	/// `{"tag": "V", "content": {"f": ...}}`, see `#[serde(tag = "tag", content = "content")]`.
+	Adjacent {
+		/// The name of the field that holds the variant name.
+		tag: F::String,
+		/// The name of the field that holds the variant fields.
+		content: F::String,
+	},
+	/// `{"f": ...}`, see `#[serde(untagged)]`.
+	Untagged,
+}
+
+impl IntoCompact for EnumTagging {
+	type Output = EnumTagging<CompactForm>;
+
+	fn into_compact(self, registry: &mut Registry) -> Self::Output {
+		match self {
+			EnumTagging::External => EnumTagging::External,
+			EnumTagging::Internal { tag } => EnumTagging::Internal {
+				tag: registry.register_string(tag),
+			},
+			EnumTagging::Adjacent { tag, content } => EnumTagging::Adjacent {
+				tag: registry.register_string(tag),
+				content: registry.register_string(content),
+			},
+			EnumTagging::Untagged => EnumTagging::Untagged,
 		}
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, From)]
-#[serde(bound = "F::TypeId: Serialize")]
+impl<S: Form, T: Form> MapForm<S, T> for EnumTagging<S> {
+	type Output = EnumTagging<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		match self {
+			EnumTagging::External => EnumTagging::External,
+			EnumTagging::Internal { tag } => EnumTagging::Internal {
+				tag: mapper.map_string(tag),
+			},
+			EnumTagging::Adjacent { tag, content } => EnumTagging::Adjacent {
+				tag: mapper.map_string(tag),
+				content: mapper.map_string(content),
+			},
+			EnumTagging::Untagged => EnumTagging::Untagged,
+		}
+	}
+}
+
+impl<F: Form> EnumTagging<F> {
+	/// Returns `true` for the default external tagging.
+	pub fn is_external(&self) -> bool {
+		matches!(self, EnumTagging::External)
+	}
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize, From)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub enum EnumVariant<F: Form = MetaForm> {
 	Unit(EnumVariantUnit<F>),
 	Struct(EnumVariantStruct<F>),
@@ -409,9 +1032,74 @@ impl IntoCompact for EnumVariant {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
+impl<S: Form, T: Form> MapForm<S, T> for EnumVariant<S> {
+	type Output = EnumVariant<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		match self {
+			EnumVariant::Unit(unit) => unit.map_form(mapper).into(),
+			EnumVariant::Struct(r#struct) => r#struct.map_form(mapper).into(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.map_form(mapper).into(),
+		}
+	}
+}
+
+impl EnumVariant {
+	/// Sets the index by which the variant is encoded.
+	pub fn with_index(self, index: u8) -> Self {
+		match self {
+			EnumVariant::Unit(unit) => unit.with_index(index).into(),
+			EnumVariant::Struct(r#struct) => r#struct.with_index(index).into(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.with_index(index).into(),
+		}
+	}
+}
+
+impl<F: Form> EnumVariant<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		match self {
+			EnumVariant::Unit(unit) => unit.name(),
+			EnumVariant::Struct(r#struct) => r#struct.name(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.name(),
+		}
+	}
+
+	/// Returns the index by which the variant is encoded if recorded.
+	pub fn index(&self) -> Option<u8> {
+		match self {
+			EnumVariant::Unit(unit) => unit.index(),
+			EnumVariant::Struct(r#struct) => r#struct.index(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.index(),
+		}
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		match self {
+			EnumVariant::Unit(unit) => unit.docs(),
+			EnumVariant::Struct(r#struct) => r#struct.docs(),
+			EnumVariant::TupleStruct(tuple_struct) => tuple_struct.docs(),
+		}
+	}
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "F::String: DeserializeOwned"))]
 pub struct EnumVariantUnit<F: Form = MetaForm> {
 	name: F::String,
+	/// The index by which the variant is encoded.
+	///
+	/// Only absent in registries serialized before indices were recorded,
+	/// in which case the index is the position of the variant.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	index: Option<u8>,
+	/// The documentation of the variant.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for EnumVariantUnit {
@@ -420,21 +1108,93 @@ impl IntoCompact for EnumVariantUnit {
 	fn into_compact(self, registry: &mut Registry) -> Self::Output {
 		EnumVariantUnit {
 			name: registry.register_string(self.name),
+			index: self.index,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for EnumVariantUnit<S> {
+	type Output = EnumVariantUnit<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		EnumVariantUnit {
+			name: mapper.map_string(self.name),
+			index: self.index,
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<F: Form> EnumVariantUnit<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the index by which the variant is encoded if recorded.
+	pub fn index(&self) -> Option<u8> {
+		self.index
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl EnumVariantUnit {
 	pub fn new(name: &'static str) -> Self {
-		Self { name }
+		Self {
+			name,
+			index: None,
+			docs: vec![],
+		}
+	}
+
+	/// Sets the index by which the variant is encoded.
+	///
+	/// Defaults to the position of the variant within its enum.
+	pub fn with_index(mut self, index: u8) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
 	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct EnumVariantStruct<F: Form = MetaForm> {
 	name: F::String,
+	/// The index by which the variant is encoded.
+	///
+	/// Only absent in registries serialized before indices were recorded,
+	/// in which case the index is the position of the variant.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	index: Option<u8>,
 	fields: Vec<NamedField<F>>,
+	/// The documentation of the variant.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for EnumVariantStruct {
@@ -443,15 +1203,67 @@ impl IntoCompact for EnumVariantStruct {
 	fn into_compact(self, registry: &mut Registry) -> Self::Output {
 		EnumVariantStruct {
 			name: registry.register_string(self.name),
+			index: self.index,
 			fields: self
 				.fields
 				.into_iter()
 				.map(|field| field.into_compact(registry))
 				.collect::<Vec<_>>(),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for EnumVariantStruct<S> {
+	type Output = EnumVariantStruct<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		EnumVariantStruct {
+			name: mapper.map_string(self.name),
+			index: self.index,
+			fields: self
+				.fields
+				.into_iter()
+				.map(|field| field.map_form(mapper))
+				.collect::<Vec<_>>(),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<F: Form> EnumVariantStruct<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the index by which the variant is encoded if recorded.
+	pub fn index(&self) -> Option<u8> {
+		self.index
+	}
+
+	/// Returns the fields of the variant.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl EnumVariantStruct {
 	pub fn new<F>(name: <MetaForm as Form>::String, fields: F) -> Self
 	where
@@ -459,16 +1271,44 @@ impl EnumVariantStruct {
 	{
 		Self {
 			name,
+			index: None,
 			fields: fields.into_iter().collect(),
+			docs: vec![],
 		}
 	}
+
+	/// Sets the index by which the variant is encoded.
+	///
+	/// Defaults to the position of the variant within its enum.
+	pub fn with_index(mut self, index: u8) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct EnumVariantTupleStruct<F: Form = MetaForm> {
 	name: F::String,
+	/// The index by which the variant is encoded.
+	///
+	/// Only absent in registries serialized before indices were recorded,
+	/// in which case the index is the position of the variant.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	index: Option<u8>,
 	fields: Vec<UnnamedField<F>>,
+	/// The documentation of the variant.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	docs: Vec<F::String>,
 }
 
 impl IntoCompact for EnumVariantTupleStruct {
@@ -477,15 +1317,67 @@ impl IntoCompact for EnumVariantTupleStruct {
 	fn into_compact(self, registry: &mut Registry) -> Self::Output {
 		EnumVariantTupleStruct {
 			name: registry.register_string(self.name),
+			index: self.index,
 			fields: self
 				.fields
 				.into_iter()
 				.map(|field| field.into_compact(registry))
 				.collect::<Vec<_>>(),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| registry.register_string(doc))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for EnumVariantTupleStruct<S> {
+	type Output = EnumVariantTupleStruct<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		EnumVariantTupleStruct {
+			name: mapper.map_string(self.name),
+			index: self.index,
+			fields: self
+				.fields
+				.into_iter()
+				.map(|field| field.map_form(mapper))
+				.collect::<Vec<_>>(),
+			docs: self
+				.docs
+				.into_iter()
+				.map(|doc| mapper.map_string(doc))
+				.collect::<Vec<_>>(),
 		}
 	}
 }
 
+impl<F: Form> EnumVariantTupleStruct<F> {
+	/// Returns the name of the variant.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the index by which the variant is encoded if recorded.
+	pub fn index(&self) -> Option<u8> {
+		self.index
+	}
+
+	/// Returns the fields of the variant.
+	pub fn fields(&self) -> &[UnnamedField<F>] {
+		&self.fields
+	}
+
+	/// Returns the documentation of the variant.
+	pub fn docs(&self) -> &[F::String] {
+		&self.docs
+	}
+}
+
 impl EnumVariantTupleStruct {
 	pub fn new<F>(name: <MetaForm as Form>::String, fields: F) -> Self
 	where
@@ -493,13 +1385,32 @@ impl EnumVariantTupleStruct {
 	{
 		Self {
 			name,
+			index: None,
 			fields: fields.into_iter().collect(),
+			docs: vec![],
 		}
 	}
+
+	/// Sets the index by which the variant is encoded.
+	///
+	/// Defaults to the position of the variant within its enum.
+	pub fn with_index(mut self, index: u8) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	/// Sets the documentation of the variant.
+	pub fn with_docs(mut self, docs: &[&'static str]) -> Self {
+		self.docs = docs.to_vec();
+		self
+	}
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeDefUnion<F: Form = MetaForm> {
 	fields: Vec<NamedField<F>>,
 }
@@ -518,6 +1429,30 @@ impl IntoCompact for TypeDefUnion {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeDefUnion<S> {
+	type Output = TypeDefUnion<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeDefUnion {
+			fields: self
+				.fields
+				.into_iter()
+				.map(|field| field.map_form(mapper))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> TypeDefUnion<F> {
+	/// Returns the fields of the union.
+	pub fn fields(&self) -> &[NamedField<F>] {
+		&self.fields
+	}
+}
+
 impl TypeDefUnion {
 	pub fn new<F>(fields: F) -> Self
 	where
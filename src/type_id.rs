@@ -17,12 +17,12 @@
 use crate::tm_std::*;
 
 use crate::{
-	form::{CompactForm, Form, MetaForm},
+	form::{CompactForm, Form, FormMapper, MapForm, MetaForm},
 	utils::is_rust_identifier,
 	IntoCompact, MetaType, Metadata, Registry,
 };
 use derive_more::From;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// Implementors return their meta type identifiers.
 pub trait HasTypeId {
@@ -36,7 +36,8 @@ pub trait HasTypeId {
 /// The first segment represents the crate name in which the type has been defined.
 ///
 /// Rust prelude type may have an empty namespace definition.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(deserialize = "F::String: DeserializeOwned"))]
 pub struct Namespace<F: Form = MetaForm> {
 	/// The segments of the namespace.
 	segments: Vec<F::String>,
@@ -69,6 +70,30 @@ impl IntoCompact for Namespace {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for Namespace<S> {
+	type Output = Namespace<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		Namespace {
+			segments: self
+				.segments
+				.into_iter()
+				.map(|seg| mapper.map_string(seg))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> Namespace<F> {
+	/// Returns the segments of the namespace.
+	pub fn segments(&self) -> &[F::String] {
+		&self.segments
+	}
+}
+
 impl Namespace {
 	/// Creates a new namespace from the given segments.
 	pub fn new<S>(segments: S) -> Result<Self, NamespaceError>
@@ -76,7 +101,7 @@ impl Namespace {
 		S: IntoIterator<Item = <MetaForm as Form>::String>,
 	{
 		let segments = segments.into_iter().collect::<Vec<_>>();
-		if segments.len() == 0 {
+		if segments.is_empty() {
 			return Err(NamespaceError::MissingSegments);
 		}
 		if let Some(err_at) = segments.iter().position(|seg| !is_rust_identifier(seg)) {
@@ -90,6 +115,7 @@ impl Namespace {
 	/// # Note
 	///
 	/// Module path is generally obtained from the `module_path!` Rust macro.
+	#[allow(clippy::should_implement_trait)]
 	pub fn from_str(module_path: <MetaForm as Form>::String) -> Result<Self, NamespaceError> {
 		Self::new(module_path.split("::"))
 	}
@@ -100,17 +126,18 @@ impl Namespace {
 	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, From, Debug, Serialize)]
-#[serde(bound = "
-	F::TypeId: Serialize,
-	F::IndirectTypeId: Serialize
-")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, From, Debug, Serialize, Deserialize)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize, F::IndirectTypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned, F::IndirectTypeId: DeserializeOwned"
+))]
 pub enum TypeId<F: Form = MetaForm> {
 	Custom(TypeIdCustom<F>),
 	Slice(TypeIdSlice<F>),
 	Array(TypeIdArray<F>),
 	Tuple(TypeIdTuple<F>),
 	Primitive(TypeIdPrimitive),
+	Parameter(TypeIdParameter),
 }
 
 impl IntoCompact for TypeId {
@@ -123,11 +150,30 @@ impl IntoCompact for TypeId {
 			TypeId::Array(array) => array.into_compact(registry).into(),
 			TypeId::Tuple(tuple) => tuple.into_compact(registry).into(),
 			TypeId::Primitive(primitive) => primitive.into(),
+			TypeId::Parameter(parameter) => parameter.into(),
+		}
+	}
+}
+
+impl<S: Form, T: Form> MapForm<S, T> for TypeId<S> {
+	type Output = TypeId<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		match self {
+			TypeId::Custom(custom) => custom.map_form(mapper).into(),
+			TypeId::Slice(slice) => slice.map_form(mapper).into(),
+			TypeId::Array(array) => array.map_form(mapper).into(),
+			TypeId::Tuple(tuple) => tuple.map_form(mapper).into(),
+			TypeId::Primitive(primitive) => primitive.into(),
+			TypeId::Parameter(parameter) => parameter.into(),
 		}
 	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum TypeIdPrimitive {
 	Bool,
@@ -143,10 +189,49 @@ pub enum TypeIdPrimitive {
 	I32,
 	I64,
 	I128,
+	/// A 32-bit IEEE 754 floating point number.
+	F32,
+	/// A 64-bit IEEE 754 floating point number.
+	F64,
+	/// The unit type `()`.
+	Unit,
+	/// The never type `!` which has no values, e.g. `core::convert::Infallible`.
+	Never,
+}
+
+/// Refers to a generic parameter of the enclosing generic type definition.
+///
+/// Shared generic type definitions refer to their generic parameters
+/// instead of concrete types. The index is the position of the
+/// generic parameter in the generic parameters of the definition.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct TypeIdParameter {
+	index: u16,
+}
+
+impl TypeIdParameter {
+	pub fn new(index: u16) -> Self {
+		Self { index }
+	}
+
+	/// Returns the index of the generic parameter.
+	pub fn index(&self) -> u16 {
+		self.index
+	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::TypeId: Serialize")]
+/// Stands in for the generic parameter at position `INDEX`.
+///
+/// Instantiating a generic type with these markers, e.g. `Option<GenericParameter<0>>`,
+/// yields its shared generic definition. See `HasTypeDef::generic_type`.
+pub enum GenericParameter<const INDEX: u16> {}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(
+	serialize = "F::TypeId: Serialize",
+	deserialize = "F::String: DeserializeOwned, F::TypeId: DeserializeOwned"
+))]
 pub struct TypeIdCustom<F: Form = MetaForm> {
 	name: F::String,
 	namespace: Namespace<F>,
@@ -170,6 +255,42 @@ impl IntoCompact for TypeIdCustom {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeIdCustom<S> {
+	type Output = TypeIdCustom<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeIdCustom {
+			name: mapper.map_string(self.name),
+			namespace: self.namespace.map_form(mapper),
+			type_params: self
+				.type_params
+				.into_iter()
+				.map(|param| mapper.map_type_id(param))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
+impl<F: Form> TypeIdCustom<F> {
+	/// Returns the name of the custom type.
+	pub fn name(&self) -> &F::String {
+		&self.name
+	}
+
+	/// Returns the namespace in which the custom type has been defined.
+	pub fn namespace(&self) -> &Namespace<F> {
+		&self.namespace
+	}
+
+	/// Returns the type parameters of the custom type.
+	pub fn type_params(&self) -> &[F::TypeId] {
+		&self.type_params
+	}
+}
+
 impl TypeIdCustom {
 	pub fn new<T>(name: &'static str, namespace: Namespace, type_params: T) -> Self
 	where
@@ -183,8 +304,11 @@ impl TypeIdCustom {
 	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::IndirectTypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(
+	serialize = "F::IndirectTypeId: Serialize",
+	deserialize = "F::IndirectTypeId: DeserializeOwned"
+))]
 pub struct TypeIdArray<F: Form = MetaForm> {
 	pub len: u16,
 	#[serde(rename = "type")]
@@ -202,14 +326,28 @@ impl IntoCompact for TypeIdArray {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeIdArray<S> {
+	type Output = TypeIdArray<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeIdArray {
+			len: self.len,
+			type_param: mapper.map_indirect_type_id(self.type_param),
+		}
+	}
+}
+
 impl TypeIdArray {
 	pub fn new(len: u16, type_param: MetaType) -> Self {
 		Self { len, type_param }
 	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::TypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(serialize = "F::TypeId: Serialize", deserialize = "F::TypeId: DeserializeOwned"))]
 pub struct TypeIdTuple<F: Form = MetaForm> {
 	#[serde(rename = "type")]
 	pub type_params: Vec<F::TypeId>,
@@ -229,6 +367,23 @@ impl IntoCompact for TypeIdTuple {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeIdTuple<S> {
+	type Output = TypeIdTuple<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeIdTuple {
+			type_params: self
+				.type_params
+				.into_iter()
+				.map(|param| mapper.map_type_id(param))
+				.collect::<Vec<_>>(),
+		}
+	}
+}
+
 impl TypeIdTuple {
 	pub fn new<T>(type_params: T) -> Self
 	where
@@ -244,8 +399,11 @@ impl TypeIdTuple {
 	}
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Debug)]
-#[serde(bound = "F::IndirectTypeId: Serialize")]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(
+	serialize = "F::IndirectTypeId: Serialize",
+	deserialize = "F::IndirectTypeId: DeserializeOwned"
+))]
 pub struct TypeIdSlice<F: Form = MetaForm> {
 	#[serde(rename = "type")]
 	type_param: F::IndirectTypeId,
@@ -261,6 +419,26 @@ impl IntoCompact for TypeIdSlice {
 	}
 }
 
+impl<S: Form, T: Form> MapForm<S, T> for TypeIdSlice<S> {
+	type Output = TypeIdSlice<T>;
+
+	fn map_form<M>(self, mapper: &mut M) -> Self::Output
+	where
+		M: FormMapper<S, T>,
+	{
+		TypeIdSlice {
+			type_param: mapper.map_indirect_type_id(self.type_param),
+		}
+	}
+}
+
+impl<F: Form> TypeIdSlice<F> {
+	/// Returns the element type of the slice.
+	pub fn type_param(&self) -> &F::IndirectTypeId {
+		&self.type_param
+	}
+}
+
 impl TypeIdSlice {
 	pub fn new(type_param: MetaType) -> Self {
 		Self { type_param }
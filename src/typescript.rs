@@ -0,0 +1,728 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of TypeScript declarations for registered types.
+//!
+//! The declarations describe the JSON representation of values as produced by `serde_json`
+//! in the same way as the `json_schema` module:
+//!
+//! - Structs are interfaces.
+//! - Tuples and tuple structs are tuple types.
+//!   Tuple structs with a single field are aliases of their field
+//!   and unit structs as well as the unit tuple are `null`.
+//! - Enums are unions discriminated by the property named after their variant by default.
+//!   Unit variants are string literals. Internally and adjacently tagged enums are
+//!   discriminated by their tag property and untagged enums are unions of their variant fields.
+//! - Flattened fields of structs are intersected with the enclosing object.
+//! - C-like enums are unions of the string literals of their variant names.
+//! - `Option<T>` is `T | null` and slices, arrays and `Vec<T>` are `Array<T>`.
+//!   The sets, deques, heaps and lists of the prelude are `Array<T>` as well
+//!   and its maps are `Record<string, V>` since their keys are written as strings.
+//! - All integers are `number`.
+//!
+//! Custom types are declared within a TypeScript namespace mirroring their `Namespace`.
+//! Every instantiation of a generic type is declared separately with its type parameters
+//! appended to its name, e.g. `Result<u8, bool>` is declared as `Result_u8_bool`.
+//! Non-generic types keep their name while the names of generic instantiations are suffixed
+//! with a counter, e.g. `Result_u8_bool_2`, if they collide with another name of their namespace.
+
+use crate::tm_std::*;
+use crate::{
+	form::CompactForm, interner::UntrackedSymbol, EnumTagging, EnumVariant, NamedField, OwnedRegistry, TypeDefKind,
+	TypeId, TypeIdCustom, TypeIdPrimitive, UnnamedField,
+};
+
+/// An error that may be encountered upon generating TypeScript declarations.
+#[derive(PartialEq, Eq, Debug)]
+pub enum TypeScriptError {
+	/// If the JSON representation of a type cannot be derived from its definition.
+	///
+	/// This is the case for unions, custom types with builtin definitions and
+	/// internally tagged tuple struct variants with more than one field.
+	UnsupportedType {
+		/// The unsupported type.
+		ty: UntrackedSymbol<AnyTypeId>,
+	},
+}
+
+/// Generates the TypeScript declarations of all custom types of the registry.
+///
+/// Declarations are ordered by their namespaces and names.
+pub fn declarations(registry: &OwnedRegistry) -> Result<String, TypeScriptError> {
+	let generator = Generator::new(registry);
+	let mut namespaces = BTreeMap::<Vec<&str>, BTreeMap<String, String>>::new();
+	for (symbol, id, def) in registry.enumerate() {
+		let custom = match id {
+			TypeId::Custom(custom) if generator.is_declared(symbol, custom) => custom,
+			_ => continue,
+		};
+		let name = generator.declared_name(symbol, custom);
+		let declaration = match def.kind() {
+			TypeDefKind::Struct(r#struct) if r#struct.fields().iter().any(NamedField::is_flattened) => {
+				format!("type {} = {};", name, generator.object(r#struct.fields()))
+			}
+			TypeDefKind::Struct(r#struct) => {
+				format!("interface {} {}", name, generator.interface(r#struct.fields()))
+			}
+			TypeDefKind::TupleStruct(tuple_struct) => {
+				format!("type {} = {};", name, generator.unnamed_fields(tuple_struct.fields()))
+			}
+			TypeDefKind::ClikeEnum(clike_enum) => {
+				let variants = clike_enum
+					.variants()
+					.iter()
+					.map(|variant| string_literal(generator.string(variant.name())))
+					.collect::<Vec<_>>();
+				format!("type {} = {};", name, union(variants))
+			}
+			TypeDefKind::Enum(r#enum) => {
+				let variants = r#enum
+					.variants()
+					.iter()
+					.map(|variant| {
+						generator
+							.variant(r#enum.tagging(), variant)
+							.ok_or(TypeScriptError::UnsupportedType { ty: symbol })
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+				format!("type {} = {};", name, union(variants))
+			}
+			TypeDefKind::Builtin | TypeDefKind::Union(_) => {
+				return Err(TypeScriptError::UnsupportedType { ty: symbol })
+			}
+		};
+		namespaces
+			.entry(generator.namespace(custom))
+			.or_default()
+			.insert(name, declaration);
+	}
+
+	let mut output = String::new();
+	for (namespace, declarations) in namespaces {
+		if !output.is_empty() {
+			output.push('\n');
+		}
+		let indent = if namespace.is_empty() {
+			""
+		} else {
+			output.push_str(&format!("export namespace {} {{\n", namespace.join(".")));
+			"\t"
+		};
+		for declaration in declarations.values() {
+			for line in format!("export {}", declaration).lines() {
+				output.push_str(indent);
+				output.push_str(line);
+				output.push('\n');
+			}
+		}
+		if !namespace.is_empty() {
+			output.push_str("}\n");
+		}
+	}
+	Ok(output)
+}
+
+/// Generates the TypeScript declarations of the types of a registry.
+struct Generator<'a> {
+	registry: &'a OwnedRegistry,
+	/// The names of the declared generic instantiations after resolving collisions.
+	names: BTreeMap<UntrackedSymbol<AnyTypeId>, String>,
+}
+
+impl<'a> Generator<'a> {
+	fn new(registry: &'a OwnedRegistry) -> Self {
+		let mut generator = Generator {
+			registry,
+			names: BTreeMap::new(),
+		};
+		let (plain, generic): (Vec<_>, Vec<_>) = registry
+			.enumerate()
+			.filter_map(|(symbol, id, _)| match id {
+				TypeId::Custom(custom) if generator.is_declared(symbol, custom) => Some((symbol, custom)),
+				_ => None,
+			})
+			.partition(|(_, custom)| custom.type_params().is_empty());
+		let mut taken = plain
+			.iter()
+			.map(|(_, custom)| {
+				(
+					generator.namespace(custom),
+					String::from(generator.string(custom.name())),
+				)
+			})
+			.collect::<BTreeSet<_>>();
+		for (symbol, custom) in generic {
+			let mangled = generator.mangled_name(custom);
+			let mut name = mangled.clone();
+			let mut counter = 1;
+			while !taken.insert((generator.namespace(custom), name.clone())) {
+				counter += 1;
+				name = format!("{}_{}", mangled, counter);
+			}
+			generator.names.insert(symbol, name);
+		}
+		generator
+	}
+
+	fn string(&self, symbol: &UntrackedSymbol<&'static str>) -> &'a str {
+		self.registry
+			.resolve_string(*symbol)
+			.expect("registries only contain valid string symbols")
+	}
+
+	fn type_id(&self, symbol: UntrackedSymbol<AnyTypeId>) -> &'a TypeId<CompactForm> {
+		self.registry
+			.resolve(symbol)
+			.expect("registries only contain valid type symbols")
+			.id()
+	}
+
+	fn namespace(&self, custom: &TypeIdCustom<CompactForm>) -> Vec<&'a str> {
+		custom
+			.namespace()
+			.segments()
+			.iter()
+			.map(|segment| self.string(segment))
+			.collect()
+	}
+
+	/// Returns whether the custom type is declared instead of being inlined or left out for being parametric.
+	fn is_declared(&self, symbol: UntrackedSymbol<AnyTypeId>, custom: &TypeIdCustom<CompactForm>) -> bool {
+		self.inlined_name(custom).is_none() && !self.registry.is_parametric(symbol)
+	}
+
+	/// Returns the name of prelude types that are not declared but inlined.
+	fn inlined_name(&self, custom: &TypeIdCustom<CompactForm>) -> Option<&'a str> {
+		if !custom.namespace().segments().is_empty() {
+			return None;
+		}
+		match (self.string(custom.name()), custom.type_params().len()) {
+			(name @ "Option", 1)
+			| (name @ "Vec", 1)
+			| (name @ "BTreeSet", 1)
+			| (name @ "VecDeque", 1)
+			| (name @ "BinaryHeap", 1)
+			| (name @ "LinkedList", 1)
+			| (name @ "HashSet", 1)
+			| (name @ "BTreeMap", 2)
+			| (name @ "HashMap", 2) => Some(name),
+			_ => None,
+		}
+	}
+
+	/// Returns the type expression of prelude types that are not declared but inlined.
+	fn inlined(&self, custom: &TypeIdCustom<CompactForm>) -> Option<String> {
+		let name = self.inlined_name(custom)?;
+		let type_param = self.type_expr(*custom.type_params().last()?);
+		match name {
+			"Option" => Some(format!("{} | null", type_param)),
+			// Map keys are always written as strings.
+			"BTreeMap" | "HashMap" => Some(format!("Record<string, {}>", type_param)),
+			_ => Some(format!("Array<{}>", type_param)),
+		}
+	}
+
+	/// Returns the name under which the custom type is declared within its namespace.
+	fn declared_name(&self, symbol: UntrackedSymbol<AnyTypeId>, custom: &TypeIdCustom<CompactForm>) -> String {
+		self.names
+			.get(&symbol)
+			.cloned()
+			.unwrap_or_else(|| self.mangled_name(custom))
+	}
+
+	/// Returns the name of the custom type with its type parameters appended.
+	fn mangled_name(&self, custom: &TypeIdCustom<CompactForm>) -> String {
+		let mut name = String::from(self.string(custom.name()));
+		for type_param in custom.type_params() {
+			let path = self
+				.registry
+				.type_path(*type_param)
+				.expect("registries only contain valid type symbols");
+			for segment in path.split(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+				if !segment.is_empty() {
+					name.push('_');
+					name.push_str(segment);
+				}
+			}
+		}
+		name
+	}
+
+	/// Returns the TypeScript type expression of the type.
+	fn type_expr(&self, symbol: UntrackedSymbol<AnyTypeId>) -> String {
+		match self.type_id(symbol) {
+			TypeId::Primitive(primitive) => String::from(primitive_type(primitive)),
+			TypeId::Slice(slice) => format!("Array<{}>", self.type_expr(*slice.type_param())),
+			TypeId::Array(array) => format!("Array<{}>", self.type_expr(array.type_param)),
+			TypeId::Tuple(tuple) if tuple.type_params.is_empty() => String::from("null"),
+			TypeId::Tuple(tuple) => self.tuple(tuple.type_params.iter().cloned()),
+			TypeId::Parameter(_) => String::from("unknown"),
+			TypeId::Custom(custom) => self.inlined(custom).unwrap_or_else(|| {
+				let mut path = self.namespace(custom);
+				let name = self.declared_name(symbol, custom);
+				path.push(&name);
+				path.join(".")
+			}),
+		}
+	}
+
+	/// Returns the body of an interface with a property for every field.
+	fn interface(&self, fields: &[NamedField<CompactForm>]) -> String {
+		let mut interface = String::from("{\n");
+		for field in fields {
+			interface.push_str(&format!("\t{};\n", self.property(field)));
+		}
+		interface.push('}');
+		interface
+	}
+
+	/// Returns the type expression of an enum variant.
+	///
+	/// Returns `None` for internally tagged tuple struct variants with more than one field.
+	fn variant(&self, tagging: &EnumTagging<CompactForm>, variant: &EnumVariant<CompactForm>) -> Option<String> {
+		let name = self.string(variant.name());
+		let variant = match (tagging, variant) {
+			(EnumTagging::External, EnumVariant::Unit(_)) => string_literal(name),
+			(EnumTagging::External, _) => format!("{{ {}: {} }}", property_name(name), self.variant_fields(variant)),
+			(EnumTagging::Internal { tag }, EnumVariant::Unit(_))
+			| (EnumTagging::Adjacent { tag, .. }, EnumVariant::Unit(_)) => self.tag(tag, name),
+			(EnumTagging::Internal { tag }, EnumVariant::Struct(r#struct)) => {
+				let tag = format!("{}: {}", property_name(self.string(tag)), string_literal(name));
+				self.object_with(vec![tag], r#struct.fields())
+			}
+			(EnumTagging::Internal { tag }, EnumVariant::TupleStruct(tuple_struct)) => match tuple_struct.fields() {
+				[field] => format!("{} & {}", self.tag(tag, name), self.type_expr(*field.ty())),
+				_ => return None,
+			},
+			(EnumTagging::Adjacent { tag, content }, _) => format!(
+				"{{ {}: {}; {}: {} }}",
+				property_name(self.string(tag)),
+				string_literal(name),
+				property_name(self.string(content)),
+				self.variant_fields(variant)
+			),
+			(EnumTagging::Untagged, _) => self.variant_fields(variant),
+		};
+		Some(variant)
+	}
+
+	/// Returns an inline object type with the tag property of an enum variant.
+	fn tag(&self, tag: &UntrackedSymbol<&'static str>, name: &str) -> String {
+		format!("{{ {}: {} }}", property_name(self.string(tag)), string_literal(name))
+	}
+
+	/// Returns the type expression of the fields of an enum variant.
+	fn variant_fields(&self, variant: &EnumVariant<CompactForm>) -> String {
+		match variant {
+			EnumVariant::Unit(_) => String::from("null"),
+			EnumVariant::Struct(r#struct) => self.object(r#struct.fields()),
+			EnumVariant::TupleStruct(tuple_struct) => self.unnamed_fields(tuple_struct.fields()),
+		}
+	}
+
+	/// Returns an inline object type with a property for every field.
+	///
+	/// The object is intersected with the types of flattened fields.
+	fn object(&self, fields: &[NamedField<CompactForm>]) -> String {
+		self.object_with(Vec::new(), fields)
+	}
+
+	/// Returns an inline object type with the given properties followed by a property for every field.
+	fn object_with(&self, mut properties: Vec<String>, fields: &[NamedField<CompactForm>]) -> String {
+		properties.extend(
+			fields
+				.iter()
+				.filter(|field| !field.is_flattened())
+				.map(|field| self.property(field)),
+		);
+		let mut intersection = if properties.is_empty() {
+			Vec::new()
+		} else {
+			vec![format!("{{ {} }}", properties.join("; "))]
+		};
+		intersection.extend(
+			fields
+				.iter()
+				.filter(|field| field.is_flattened())
+				.map(|field| self.type_expr(*field.ty())),
+		);
+		if intersection.is_empty() {
+			String::from("{}")
+		} else {
+			intersection.join(" & ")
+		}
+	}
+
+	fn property(&self, field: &NamedField<CompactForm>) -> String {
+		format!(
+			"{}: {}",
+			property_name(self.string(field.name())),
+			self.type_expr(*field.ty())
+		)
+	}
+
+	fn unnamed_fields(&self, fields: &[UnnamedField<CompactForm>]) -> String {
+		match fields {
+			[] => String::from("null"),
+			[field] => self.type_expr(*field.ty()),
+			_ => self.tuple(fields.iter().map(|field| *field.ty())),
+		}
+	}
+
+	fn tuple<I>(&self, elems: I) -> String
+	where
+		I: Iterator<Item = UntrackedSymbol<AnyTypeId>>,
+	{
+		let elems = elems.map(|elem| self.type_expr(elem)).collect::<Vec<_>>();
+		format!("[{}]", elems.join(", "))
+	}
+}
+
+/// Returns the TypeScript type of the primitive.
+fn primitive_type(primitive: &TypeIdPrimitive) -> &'static str {
+	match primitive {
+		TypeIdPrimitive::Bool => "boolean",
+		TypeIdPrimitive::Char | TypeIdPrimitive::Str => "string",
+		TypeIdPrimitive::U8
+		| TypeIdPrimitive::U16
+		| TypeIdPrimitive::U32
+		| TypeIdPrimitive::U64
+		| TypeIdPrimitive::U128
+		| TypeIdPrimitive::I8
+		| TypeIdPrimitive::I16
+		| TypeIdPrimitive::I32
+		| TypeIdPrimitive::I64
+		| TypeIdPrimitive::I128
+		| TypeIdPrimitive::F32
+		| TypeIdPrimitive::F64 => "number",
+		TypeIdPrimitive::Unit => "null",
+		TypeIdPrimitive::Never => "never",
+	}
+}
+
+/// Returns the union of the types or `never` if there are none.
+fn union(types: Vec<String>) -> String {
+	if types.is_empty() {
+		return String::from("never");
+	}
+	types.join(" | ")
+}
+
+fn string_literal(string: &str) -> String {
+	format!("\"{}\"", string)
+}
+
+/// Returns the name as a property name, quoting it if it is not an identifier.
+fn property_name(name: &str) -> String {
+	let is_identifier = name
+		.chars()
+		.enumerate()
+		.all(|(i, c)| c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+	if is_identifier && !name.is_empty() {
+		String::from(name)
+	} else {
+		string_literal(name)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	#[allow(unused)]
+	struct Node {
+		next: Option<Box<Node>>,
+		children: Vec<(u32, Shape)>,
+	}
+
+	impl HasTypeId for Node {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Node", Namespace::new(vec!["app", "graph"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Node {
+		fn type_def() -> TypeDef {
+			TypeDefStruct::new(vec![
+				NamedField::of::<Option<Box<Node>>>("next"),
+				NamedField::of::<Vec<(u32, Shape)>>("children"),
+			])
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	enum Shape {
+		Empty,
+		Circle(u8),
+		Rect { w: u8, h: u8 },
+	}
+
+	impl HasTypeId for Shape {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Shape", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Shape {
+		fn type_def() -> TypeDef {
+			TypeDefEnum::new(vec![
+				EnumVariantUnit::new("Empty").into(),
+				EnumVariantTupleStruct::new("Circle", vec![UnnamedField::of::<u8>()]).into(),
+				EnumVariantStruct::new("Rect", vec![NamedField::of::<u8>("w"), NamedField::of::<u8>("h")]).into(),
+			])
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	enum Color {
+		Red,
+		Green,
+	}
+
+	impl HasTypeId for Color {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Color", Namespace::new(vec!["app"]).unwrap(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Color {
+		fn type_def() -> TypeDef {
+			TypeDefClikeEnum::new(vec![
+				ClikeEnumVariant::new("Red", 0u64),
+				ClikeEnumVariant::new("Green", 1u64),
+			])
+			.into()
+		}
+	}
+
+	#[allow(unused)]
+	struct Pair(bool, [String; 2]);
+
+	impl HasTypeId for Pair {
+		fn type_id() -> TypeId {
+			TypeIdCustom::new("Pair", Namespace::prelude(), vec![]).into()
+		}
+	}
+
+	impl HasTypeDef for Pair {
+		fn type_def() -> TypeDef {
+			TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>(), UnnamedField::of::<[String; 2]>()]).into()
+		}
+	}
+
+	#[test]
+	fn declarations_of_custom_types() {
+		let mut registry = Registry::new();
+		registry.register_type(&Node::meta_type());
+		registry.register_type(&<Result<Color, Pair>>::meta_type());
+		let expected = r#"export type Pair = [boolean, Array<string>];
+export type Result_app_Color_Pair = { Ok: app.Color } | { Err: Pair };
+
+export namespace app {
+	export type Color = "Red" | "Green";
+	export type Shape = "Empty" | { Circle: number } | { Rect: { w: number; h: number } };
+}
+
+export namespace app.graph {
+	export interface Node {
+		next: app.graph.Node | null;
+		children: Array<[number, app.Shape]>;
+	}
+}
+"#;
+		assert_eq!(declarations(&registry.into()), Ok(String::from(expected)));
+	}
+
+	macro_rules! tagged_enum {
+		( $name:ident, $tagging:expr ) => {
+			#[allow(unused)]
+			enum $name {
+				Empty,
+				Node(Node),
+				Rect { w: u8 },
+			}
+
+			impl HasTypeId for $name {
+				fn type_id() -> TypeId {
+					TypeIdCustom::new(stringify!($name), Namespace::prelude(), vec![]).into()
+				}
+			}
+
+			impl HasTypeDef for $name {
+				fn type_def() -> TypeDef {
+					TypeDefEnum::new(vec![
+						EnumVariantUnit::new("Empty").into(),
+						EnumVariantTupleStruct::new("Node", vec![UnnamedField::of::<Node>()]).into(),
+						EnumVariantStruct::new("Rect", vec![NamedField::of::<u8>("w")]).into(),
+					])
+					.with_tagging($tagging)
+					.into()
+				}
+			}
+		};
+	}
+
+	#[test]
+	fn tagged_enums_and_flattened_fields() {
+		tagged_enum!(Internal, EnumTagging::Internal { tag: "type" });
+		tagged_enum!(Adjacent, EnumTagging::Adjacent { tag: "t", content: "c" });
+		tagged_enum!(Untagged, EnumTagging::Untagged);
+
+		#[allow(unused)]
+		struct Labeled {
+			label: String,
+			node: Node,
+		}
+
+		impl HasTypeId for Labeled {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Labeled", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Labeled {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![
+					NamedField::of::<String>("label"),
+					NamedField::of::<Node>("node").flattened(),
+				])
+				.into()
+			}
+		}
+
+		let mut registry = Registry::new();
+		registry.register_type(&Internal::meta_type());
+		registry.register_type(&Adjacent::meta_type());
+		registry.register_type(&Untagged::meta_type());
+		registry.register_type(&Labeled::meta_type());
+		let declarations = declarations(&registry.into()).unwrap();
+		let expected = r#"export type Adjacent = { t: "Empty" } | { t: "Node"; c: app.graph.Node } | { t: "Rect"; c: { w: number } };
+export type Internal = { type: "Empty" } | { type: "Node" } & app.graph.Node | { type: "Rect"; w: number };
+export type Labeled = { label: string } & app.graph.Node;
+export type Untagged = null | app.graph.Node | { w: number };
+"#;
+		assert!(declarations.starts_with(expected), "{}", declarations);
+	}
+
+	#[test]
+	fn prelude_collections_are_inlined() {
+		#[allow(unused)]
+		struct Inventory {
+			counts: BTreeMap<u32, String>,
+			tags: BTreeSet<String>,
+			queue: VecDeque<bool>,
+		}
+
+		impl HasTypeId for Inventory {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Inventory", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Inventory {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![
+					NamedField::of::<BTreeMap<u32, String>>("counts"),
+					NamedField::of::<BTreeSet<String>>("tags"),
+					NamedField::of::<VecDeque<bool>>("queue"),
+				])
+				.into()
+			}
+		}
+
+		let mut registry = Registry::new();
+		registry.register_type(&Inventory::meta_type());
+		let expected = r#"export interface Inventory {
+	counts: Record<string, string>;
+	tags: Array<string>;
+	queue: Array<boolean>;
+}
+"#;
+		assert_eq!(declarations(&registry.into()), Ok(String::from(expected)));
+	}
+
+	#[test]
+	fn colliding_names_are_suffixed() {
+		#[allow(unused)]
+		struct Colliding;
+
+		impl HasTypeId for Colliding {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Result_u8_bool", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Colliding {
+			fn type_def() -> TypeDef {
+				TypeDefStruct::new(vec![NamedField::of::<Result<u8, bool>>("result")]).into()
+			}
+		}
+
+		let mut registry = Registry::new();
+		registry.register_type(&Colliding::meta_type());
+		let expected = r#"export interface Result_u8_bool {
+	result: Result_u8_bool_2;
+}
+export type Result_u8_bool_2 = { Ok: number } | { Err: boolean };
+"#;
+		assert_eq!(declarations(&registry.into()), Ok(String::from(expected)));
+	}
+
+	#[test]
+	fn unions_are_unsupported() {
+		#[allow(unused)]
+		union Bits {
+			int: u32,
+		}
+
+		impl HasTypeId for Bits {
+			fn type_id() -> TypeId {
+				TypeIdCustom::new("Bits", Namespace::prelude(), vec![]).into()
+			}
+		}
+
+		impl HasTypeDef for Bits {
+			fn type_def() -> TypeDef {
+				TypeDefUnion::new(vec![NamedField::of::<u32>("int")]).into()
+			}
+		}
+
+		let mut registry = Registry::new();
+		let symbol = registry.register_type(&Bits::meta_type());
+		assert_eq!(
+			declarations(&registry.into()),
+			Err(TypeScriptError::UnsupportedType { ty: symbol })
+		);
+	}
+
+	#[test]
+	fn primitive_types() {
+		assert_eq!(primitive_type(&TypeIdPrimitive::F64), "number");
+		assert_eq!(primitive_type(&TypeIdPrimitive::Unit), "null");
+		assert_eq!(primitive_type(&TypeIdPrimitive::Never), "never");
+	}
+
+	#[test]
+	fn property_names() {
+		assert_eq!(property_name("field_1"), "field_1");
+		assert_eq!(property_name("$ref"), "$ref");
+		assert_eq!(property_name("1st"), "\"1st\"");
+		assert_eq!(property_name("r#type"), "\"r#type\"");
+	}
+}
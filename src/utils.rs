@@ -23,10 +23,8 @@ pub fn is_rust_identifier(s: &str) -> bool {
 	}
 	if let Some((&head, tail)) = s.as_bytes().split_first() {
 		// Check if head and tail make up a proper Rust identifier.
-		let head_ok = head == b'_' || head >= b'a' && head <= b'z' || head >= b'A' && head <= b'Z';
-		let tail_ok = tail
-			.iter()
-			.all(|&ch| ch == b'_' || ch >= b'a' && ch <= b'z' || ch >= b'A' && ch <= b'Z' || ch >= b'0' && ch <= b'9');
+		let head_ok = head == b'_' || head.is_ascii_alphabetic();
+		let tail_ok = tail.iter().all(|&ch| ch == b'_' || ch.is_ascii_alphanumeric());
 		head_ok && tail_ok
 	} else {
 		// String is empty and thus not a valid Rust identifier.
@@ -0,0 +1,106 @@
+// Copyright 2019
+//     by  Centrality Investments Ltd.
+//     and Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dynamically typed values whose structure is described by a registry.
+
+use crate::tm_std::*;
+use derive_more::From;
+
+/// A dynamically typed value.
+///
+/// The shape of a value mirrors the type identifier and
+/// definition of the registered type it has been decoded from.
+#[derive(PartialEq, Eq, Clone, From, Debug)]
+pub enum Value {
+	/// A primitive value such as an integer or a string.
+	Primitive(Primitive),
+	/// A struct or tuple struct value.
+	Composite(Composite),
+	/// An enum value.
+	Variant(Variant),
+	/// A value of a dynamically sized slice type.
+	Sequence(Vec<Value>),
+	/// A value of a fixed size array type.
+	Array(Vec<Value>),
+	/// A value of a tuple type.
+	Tuple(Vec<Value>),
+}
+
+/// A primitive value.
+#[derive(PartialEq, Eq, Clone, From, Debug)]
+pub enum Primitive {
+	Bool(bool),
+	Char(char),
+	Str(String),
+	U8(u8),
+	U16(u16),
+	U32(u32),
+	U64(u64),
+	U128(u128),
+	I8(i8),
+	I16(i16),
+	I32(i32),
+	I64(i64),
+	I128(i128),
+	/// A 32-bit float as its little endian IEEE 754 bytes.
+	///
+	/// Floats are kept as bytes so that values remain comparable
+	/// and can be handled without floating point support.
+	F32([u8; 4]),
+	/// A 64-bit float as its little endian IEEE 754 bytes.
+	F64([u8; 8]),
+	/// The unit value `()`.
+	Unit,
+}
+
+impl Primitive {
+	/// Returns `true` if the primitive is an integer equal to zero.
+	pub fn is_zero(&self) -> bool {
+		match self {
+			Primitive::U8(value) => *value == 0,
+			Primitive::U16(value) => *value == 0,
+			Primitive::U32(value) => *value == 0,
+			Primitive::U64(value) => *value == 0,
+			Primitive::U128(value) => *value == 0,
+			Primitive::I8(value) => *value == 0,
+			Primitive::I16(value) => *value == 0,
+			Primitive::I32(value) => *value == 0,
+			Primitive::I64(value) => *value == 0,
+			Primitive::I128(value) => *value == 0,
+			_ => false,
+		}
+	}
+}
+
+/// The fields of a struct, tuple struct or enum variant value.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Composite {
+	/// Fields of a struct or struct variant.
+	Named(Vec<(String, Value)>),
+	/// Fields of a tuple struct or tuple struct variant.
+	///
+	/// Unit structs and unit variants have no unnamed fields.
+	Unnamed(Vec<Value>),
+}
+
+/// The value of an enum or C-like enum.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Variant {
+	/// The name of the variant.
+	pub name: String,
+	/// The fields of the variant.
+	pub fields: Composite,
+}
@@ -14,12 +14,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use core::marker::PhantomData;
+use serde::Serialize;
 use type_metadata::{
-	tuple_meta_type, ClikeEnumVariant, EnumVariantStruct, EnumVariantTupleStruct, EnumVariantUnit, HasTypeDef,
-	HasTypeId, MetaType, Metadata, NamedField, Namespace, TypeDefClikeEnum, TypeDefEnum, TypeDefStruct,
-	TypeDefTupleStruct, TypeDefUnion, TypeId, TypeIdCustom, UnnamedField,
+	tuple_meta_type, ClikeEnumVariant, EnumTagging, EnumVariantStruct, EnumVariantTupleStruct, EnumVariantUnit,
+	GenericParameter, HasTypeDef, HasTypeId, Metadata, NamedField, Namespace, TypeDef, TypeDefClikeEnum, TypeDefEnum,
+	TypeDefStruct, TypeDefTupleStruct, TypeDefUnion, TypeId, TypeIdCustom, UnnamedField,
 };
 
+/// A facade re-exporting the crate under another path.
+mod facade {
+	pub use type_metadata as metadata;
+}
+
 fn assert_type_id<T, E>(expected: E)
 where
 	T: HasTypeId + ?Sized,
@@ -46,11 +53,13 @@ fn struct_derive() {
 	let type_id = TypeIdCustom::new("S", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool, u8));
 	assert_type_id!(S<bool, u8>, type_id.clone());
 
-	let type_def = TypeDefStruct::new(vec![
-		NamedField::new("t", bool::meta_type()),
-		NamedField::new("u", u8::meta_type()),
-	])
-	.into();
+	let type_def = TypeDef::new(
+		vec!["T", "U"],
+		TypeDefStruct::new(vec![
+			NamedField::new("t", bool::meta_type()),
+			NamedField::new("u", u8::meta_type()),
+		]),
+	);
 	assert_eq!(<S<bool, u8>>::type_def(), type_def);
 
 	// With "`Self` typed" fields
@@ -66,11 +75,13 @@ fn struct_derive() {
 
 	assert_eq!(
 		SelfTyped::type_def(),
-		TypeDefStruct::new(vec![
-			NamedField::new("t", <Box<S<bool, u8>>>::meta_type()),
-			NamedField::new("u", bool::meta_type()),
-		])
-		.into(),
+		TypeDef::new(
+			vec!["T", "U"],
+			TypeDefStruct::new(vec![
+				NamedField::new("t", <Box<S<bool, u8>>>::meta_type()),
+				NamedField::new("u", bool::meta_type()),
+			]),
+		),
 	);
 }
 
@@ -83,7 +94,7 @@ fn tuple_struct_derive() {
 	let type_id = TypeIdCustom::new("S", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
 	assert_type_id!(S<bool>, type_id);
 
-	let type_def = TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>()]).into();
+	let type_def = TypeDef::new(vec!["T"], TypeDefTupleStruct::new(vec![UnnamedField::of::<bool>()]));
 	assert_eq!(<S<bool>>::type_def(), type_def);
 }
 
@@ -120,6 +131,73 @@ fn c_like_enum_derive() {
 	assert_eq!(E::type_def(), type_def);
 }
 
+#[test]
+fn c_like_enum_discriminants_derive() {
+	const BASE: i64 = 1 << 40;
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(i64)]
+	enum Signed {
+		A = -2,
+		B,
+		C,
+		D = BASE,
+		E,
+		F = 1 << 3,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(u128)]
+	enum Wide {
+		Max = u64::MAX as u128 + 1,
+		Next,
+		Top = u128::MAX,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[repr(u8)]
+	enum WithData {
+		A(u8) = 3,
+		B,
+	}
+
+	assert_eq!(
+		Signed::type_def(),
+		TypeDefClikeEnum::new(vec![
+			ClikeEnumVariant::new("A", -2),
+			ClikeEnumVariant::new("B", -1),
+			ClikeEnumVariant::new("C", 0),
+			ClikeEnumVariant::new("D", 1i64 << 40),
+			ClikeEnumVariant::new("E", (1i64 << 40) + 1),
+			ClikeEnumVariant::new("F", 8),
+		])
+		.with_repr::<i64>()
+		.into(),
+	);
+	assert_eq!(
+		Wide::type_def(),
+		TypeDefClikeEnum::new(vec![
+			ClikeEnumVariant::new("Max", i128::from(u64::MAX) + 1),
+			ClikeEnumVariant::new("Next", i128::from(u64::MAX) + 2),
+			// Discriminants above `i128::MAX` are stored by their two's complement.
+			ClikeEnumVariant::new("Top", -1),
+		])
+		.with_repr::<u128>()
+		.into(),
+	);
+	assert_eq!(
+		WithData::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("A", vec![UnnamedField::new(u8::meta_type())]).into(),
+			EnumVariantUnit::new("B").into(),
+		])
+		.into(),
+	);
+}
+
 #[test]
 fn enum_derive() {
 	#[allow(unused)]
@@ -133,12 +211,14 @@ fn enum_derive() {
 	let type_id = TypeIdCustom::new("E", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
 	assert_type_id!(E<bool>, type_id);
 
-	let type_def = TypeDefEnum::new(vec![
-		EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()]).into(),
-		EnumVariantStruct::new("B", vec![NamedField::new("b", bool::meta_type())]).into(),
-		EnumVariantUnit::new("C").into(),
-	])
-	.into();
+	let type_def = TypeDef::new(
+		vec!["T"],
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("A", vec![UnnamedField::of::<bool>()]).into(),
+			EnumVariantStruct::new("B", vec![NamedField::new("b", bool::meta_type())]).into(),
+			EnumVariantUnit::new("C").into(),
+		]),
+	);
 	assert_eq!(<E<bool>>::type_def(), type_def);
 }
 
@@ -154,6 +234,581 @@ fn union_derive() {
 	let type_id = TypeIdCustom::new("U", Namespace::new(vec!["derive"]).unwrap(), tuple_meta_type!(bool));
 	assert_type_id!(U<bool>, type_id);
 
-	let type_def = TypeDefUnion::new(vec![NamedField::new("u", bool::meta_type())]).into();
+	let type_def = TypeDef::new(vec!["T"], TypeDefUnion::new(vec![NamedField::new("u", bool::meta_type())]));
 	assert_eq!(<U<bool>>::type_def(), type_def);
 }
+
+#[test]
+fn generic_type_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Pair<T, U> {
+		pub first: Option<T>,
+		pub second: U,
+	}
+
+	#[allow(unused)]
+	#[derive(TypeId, TypeDef)]
+	struct Separate<T> {
+		pub t: T,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Bounded<T: Clone> {
+		pub t: T,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Plain {
+		pub a: u8,
+	}
+
+	assert_eq!(
+		<Pair<bool, u8>>::generic_type(),
+		Some(<Pair<GenericParameter<0>, GenericParameter<1>>>::meta_type())
+	);
+	assert_eq!(
+		<Pair<GenericParameter<0>, GenericParameter<1>>>::type_def(),
+		TypeDef::new(
+			vec!["T", "U"],
+			TypeDefStruct::new(vec![
+				NamedField::new("first", <Option<GenericParameter<0>>>::meta_type()),
+				NamedField::new("second", <GenericParameter<1>>::meta_type()),
+			]),
+		),
+	);
+	assert_eq!(
+		<Separate<bool>>::generic_type(),
+		Some(<Separate<GenericParameter<0>>>::meta_type())
+	);
+	assert_eq!(<Bounded<bool>>::generic_type(), None);
+	assert_eq!(Plain::generic_type(), None);
+}
+
+#[test]
+fn docs_derive() {
+	/// A point
+	/// in space.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Point {
+		/// The horizontal coordinate.
+		pub x: u8,
+		pub y: u8,
+	}
+
+	/// The kind of a shape.
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum Shape {
+		/// A circle.
+		Circle {
+			/// The radius of the circle.
+			radius: u8,
+		},
+		Square(
+			/// The side length of the square.
+			u8,
+		),
+		/// No shape at all.
+		Empty,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum Level {
+		/// The lowest level.
+		Low,
+		High,
+	}
+
+	assert_eq!(
+		Point::type_def(),
+		TypeDef::from(TypeDefStruct::new(vec![
+			NamedField::new("x", u8::meta_type()).with_docs(&["The horizontal coordinate."]),
+			NamedField::new("y", u8::meta_type()),
+		]))
+		.with_docs(&["A point", "in space."]),
+	);
+	assert_eq!(
+		Shape::type_def(),
+		TypeDef::from(TypeDefEnum::new(vec![
+			EnumVariantStruct::new(
+				"Circle",
+				vec![NamedField::new("radius", u8::meta_type()).with_docs(&["The radius of the circle."])],
+			)
+			.with_docs(&["A circle."])
+			.into(),
+			EnumVariantTupleStruct::new(
+				"Square",
+				vec![UnnamedField::new(u8::meta_type()).with_docs(&["The side length of the square."])],
+			)
+			.into(),
+			EnumVariantUnit::new("Empty").with_docs(&["No shape at all."]).into(),
+		]))
+		.with_docs(&["The kind of a shape."]),
+	);
+	assert_eq!(
+		Level::type_def(),
+		TypeDefClikeEnum::new(vec![
+			ClikeEnumVariant::new("Low", 0u64).with_docs(&["The lowest level."]),
+			ClikeEnumVariant::new("High", 1u64),
+		])
+		.into(),
+	);
+}
+
+#[test]
+fn field_attributes_derive() {
+	#[allow(unused)]
+	struct Compact(u64);
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct S {
+		#[type_metadata(skip)]
+		pub cache: Vec<u8>,
+		#[type_metadata(rename = "type")]
+		pub kind: u8,
+		#[type_metadata(with = "u32", rename = "len")]
+		pub length: Compact,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	enum E {
+		A(#[type_metadata(skip)] u8, #[type_metadata(with = "bool")] u8),
+		B,
+	}
+
+	assert_eq!(
+		S::type_def(),
+		TypeDefStruct::new(vec![
+			NamedField::new("type", u8::meta_type()),
+			NamedField::new("len", u32::meta_type()),
+		])
+		.into(),
+	);
+	assert_eq!(
+		E::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("A", vec![UnnamedField::new(bool::meta_type())]).into(),
+			EnumVariantUnit::new("B").into(),
+		])
+		.into(),
+	);
+}
+
+#[test]
+fn serde_attributes_derive() {
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(rename_all = "camelCase")]
+	struct Header {
+		block_number: u32,
+		#[serde(rename = "hash")]
+		parent_hash: [u8; 32],
+		#[serde(skip)]
+		cache: Vec<u8>,
+		#[serde(flatten)]
+		extra: Extra,
+		r#type: u8,
+		#[type_metadata(rename = "id")]
+		#[serde(rename = "ignored")]
+		identifier: u8,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	struct Extra {
+		digest: Vec<u8>,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(transparent)]
+	struct Wrapper {
+		#[serde(skip)]
+		marker: (),
+		value: u64,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+	enum Event {
+		NewHeader(Header),
+		#[serde(rename_all = "UPPERCASE")]
+		Transfer {
+			from_account: u8,
+		},
+		#[serde(skip)]
+		Internal,
+		Reset,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(tag = "type", rename_all = "kebab-case")]
+	enum Status {
+		NotReady,
+		Ready,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+	enum Level {
+		Low,
+		#[serde(rename = "hi")]
+		High,
+		#[serde(skip_serializing)]
+		Unknown,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(tag = "type", rename = "header_ref", rename_all = "camelCase")]
+	struct HeaderRef {
+		block_number: u32,
+	}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(tag = "kind")]
+	struct Ping {}
+
+	#[allow(unused)]
+	#[derive(Serialize, Metadata)]
+	#[type_metadata(serde)]
+	#[serde(untagged)]
+	enum Value {
+		Number(u64),
+		Text(String),
+	}
+
+	assert_eq!(
+		Header::type_def(),
+		TypeDefStruct::new(vec![
+			NamedField::new("blockNumber", u32::meta_type()),
+			NamedField::new("hash", <[u8; 32]>::meta_type()),
+			NamedField::new("extra", Extra::meta_type()).flattened(),
+			NamedField::new("type", u8::meta_type()),
+			NamedField::new("id", u8::meta_type()),
+		])
+		.into(),
+	);
+	assert_eq!(
+		Wrapper::type_def(),
+		TypeDefTupleStruct::new(vec![UnnamedField::new(u64::meta_type())]).into(),
+	);
+	assert_eq!(
+		Event::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("new_header", vec![UnnamedField::new(Header::meta_type())]).into(),
+			EnumVariantStruct::new("transfer", vec![NamedField::new("FROM_ACCOUNT", u8::meta_type())]).into(),
+			EnumVariantUnit::new("reset").with_index(3).into(),
+		])
+		.with_tagging(EnumTagging::Adjacent {
+			tag: "kind",
+			content: "data",
+		})
+		.into(),
+	);
+	assert_eq!(
+		Status::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantUnit::new("not-ready").into(),
+			EnumVariantUnit::new("ready").into(),
+		])
+		.with_tagging(EnumTagging::Internal { tag: "type" })
+		.into(),
+	);
+	assert_eq!(
+		Level::type_def(),
+		TypeDefClikeEnum::new(vec![ClikeEnumVariant::new("LOW", 0u64), ClikeEnumVariant::new("hi", 1u64)]).into(),
+	);
+	assert_eq!(
+		Value::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("Number", vec![UnnamedField::new(u64::meta_type())]).into(),
+			EnumVariantTupleStruct::new("Text", vec![UnnamedField::new(String::meta_type())]).into(),
+		])
+		.with_tagging(EnumTagging::Untagged)
+		.into(),
+	);
+	assert_eq!(
+		HeaderRef::type_def(),
+		TypeDefEnum::new(vec![EnumVariantStruct::new(
+			"header_ref",
+			vec![NamedField::new("blockNumber", u32::meta_type())]
+		)
+		.into()])
+		.with_tagging(EnumTagging::Internal { tag: "type" })
+		.into(),
+	);
+	assert_eq!(
+		Ping::type_def(),
+		TypeDefEnum::new(vec![EnumVariantStruct::new("Ping", vec![]).into()])
+			.with_tagging(EnumTagging::Internal { tag: "kind" })
+			.into(),
+	);
+}
+
+#[test]
+fn bounds_derive() {
+	#[allow(unused)]
+	struct Opaque;
+
+	trait Config {
+		type Balance;
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Runtime;
+
+	impl Config for Runtime {
+		type Balance = u64;
+	}
+
+	#[allow(unused)]
+	struct Testnet;
+
+	impl Config for Testnet {
+		type Balance = u128;
+	}
+
+	#[allow(unused)]
+	#[derive(TypeId, TypeDef)]
+	struct Handle<T> {
+		id: u32,
+		#[type_metadata(skip)]
+		marker: PhantomData<T>,
+	}
+
+	#[allow(unused)]
+	#[derive(TypeDef)]
+	struct Account<C: Config> {
+		balance: C::Balance,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Tagged<T, U> {
+		value: U,
+		marker: PhantomData<T>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Wallet<C: Config> {
+		balance: C::Balance,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[type_metadata(bound = "C: Metadata + 'static, C::Balance: Metadata + 'static")]
+	struct Ledger<C: Config> {
+		balances: Vec<C::Balance>,
+	}
+
+	#[allow(unused)]
+	#[derive(TypeDef)]
+	struct Cell<T> {
+		#[type_metadata(bound = "Vec<T>: Metadata + 'static")]
+		values: Vec<T>,
+	}
+
+	assert_type_id!(
+		Handle<Opaque>,
+		TypeIdCustom::new("Handle", Namespace::new(vec!["derive"]).unwrap(), vec![<()>::meta_type()])
+	);
+	assert_eq!(
+		Handle::<Opaque>::type_def(),
+		TypeDef::new(vec!["T"], TypeDefStruct::new(vec![NamedField::new("id", u32::meta_type())])),
+	);
+	assert_eq!(
+		Account::<Runtime>::type_def(),
+		TypeDef::new(
+			vec!["C", "C::Balance"],
+			TypeDefStruct::new(vec![NamedField::new("balance", u64::meta_type())])
+		),
+	);
+	assert_type_id!(
+		Tagged<Runtime, u8>,
+		TypeIdCustom::new(
+			"Tagged",
+			Namespace::new(vec!["derive"]).unwrap(),
+			vec![<PhantomData<Runtime>>::meta_type(), u8::meta_type()]
+		)
+	);
+	assert_eq!(
+		Tagged::<Runtime, u8>::type_def(),
+		TypeDef::new(
+			vec!["T", "U"],
+			TypeDefStruct::new(vec![
+				NamedField::new("value", u8::meta_type()),
+				NamedField::new("marker", <PhantomData<Runtime>>::meta_type()),
+			])
+		),
+	);
+	assert_eq!(
+		Tagged::<Runtime, u8>::generic_type(),
+		Some(<Tagged<GenericParameter<0>, GenericParameter<1>>>::meta_type())
+	);
+	assert_type_id!(
+		Wallet<Testnet>,
+		TypeIdCustom::new(
+			"Wallet",
+			Namespace::new(vec!["derive"]).unwrap(),
+			vec![<()>::meta_type(), u128::meta_type()]
+		)
+	);
+	assert_eq!(
+		Wallet::<Testnet>::type_def(),
+		TypeDef::new(
+			vec!["C", "C::Balance"],
+			TypeDefStruct::new(vec![NamedField::new("balance", u128::meta_type())])
+		),
+	);
+	assert_type_id!(
+		Ledger<Runtime>,
+		TypeIdCustom::new("Ledger", Namespace::new(vec!["derive"]).unwrap(), vec![Runtime::meta_type()])
+	);
+	assert_eq!(
+		Ledger::<Runtime>::type_def(),
+		TypeDef::new(
+			vec!["C"],
+			TypeDefStruct::new(vec![NamedField::new("balances", <Vec<u64>>::meta_type())])
+		),
+	);
+	assert_eq!(
+		Cell::<u8>::type_def(),
+		TypeDef::new(
+			vec!["T"],
+			TypeDefStruct::new(vec![NamedField::new("values", <Vec<u8>>::meta_type())])
+		),
+	);
+}
+
+#[test]
+fn generic_params_match_type_params_derive() {
+	trait Config {
+		type Balance;
+		type Hash;
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Runtime;
+
+	impl Config for Runtime {
+		type Balance = u64;
+		type Hash = [u8; 32];
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Marked<T, U> {
+		value: T,
+		marker: PhantomData<U>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Transfer<C: Config, T> {
+		amount: C::Balance,
+		hash: Option<C::Hash>,
+		marker: PhantomData<T>,
+	}
+
+	#[allow(unused)]
+	#[derive(Metadata)]
+	struct Skipping<T, U> {
+		value: U,
+		#[type_metadata(skip)]
+		skipped: T,
+	}
+
+	fn assert_arity<T: Metadata>() {
+		let type_params = match T::type_id() {
+			TypeId::Custom(custom) => custom.type_params().len(),
+			type_id => panic!("expected a custom type id, got {:?}", type_id),
+		};
+		assert_eq!(T::type_def().generic_params().params().len(), type_params);
+	}
+
+	assert_arity::<Marked<bool, u8>>();
+	assert_arity::<Transfer<Runtime, bool>>();
+	assert_arity::<Skipping<bool, u8>>();
+	assert_eq!(
+		Transfer::<Runtime, bool>::type_def(),
+		TypeDef::new(
+			vec!["C", "T", "C::Balance", "C::Hash"],
+			TypeDefStruct::new(vec![
+				NamedField::new("amount", u64::meta_type()),
+				NamedField::new("hash", <Option<[u8; 32]>>::meta_type()),
+				NamedField::new("marker", <PhantomData<bool>>::meta_type()),
+			])
+		),
+	);
+}
+
+#[test]
+fn crate_path_derive() {
+	#[allow(unused)]
+	#[derive(Metadata)]
+	#[type_metadata(crate = "facade::metadata")]
+	struct Reexported {
+		a: u8,
+	}
+
+	assert_type_id!(
+		Reexported,
+		TypeIdCustom::new("Reexported", Namespace::new(vec!["derive"]).unwrap(), vec![])
+	);
+	assert_eq!(
+		Reexported::type_def(),
+		TypeDefStruct::new(vec![NamedField::new("a", u8::meta_type())]).into(),
+	);
+}
+
+#[test]
+fn variant_index_derive() {
+	#[allow(unused)]
+	#[derive(Metadata, Serialize)]
+	#[type_metadata(serde)]
+	enum E {
+		#[type_metadata(index = 7)]
+		A(u8),
+		#[serde(skip)]
+		B,
+		C { c: bool },
+		#[type_metadata(index = 0)]
+		D,
+	}
+
+	assert_eq!(
+		E::type_def(),
+		TypeDefEnum::new(vec![
+			EnumVariantTupleStruct::new("A", vec![UnnamedField::new(u8::meta_type())])
+				.with_index(7)
+				.into(),
+			EnumVariantStruct::new("C", vec![NamedField::new("c", bool::meta_type())])
+				.with_index(2)
+				.into(),
+			EnumVariantUnit::new("D").with_index(0).into(),
+		])
+		.into(),
+	);
+}